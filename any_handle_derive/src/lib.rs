@@ -0,0 +1,123 @@
+//! Derive macro for `any_handle`'s `Handleable` trait. Enable the `derive`
+//! feature of `any_handle` rather than depending on this crate directly.
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Implement `Handleable` for a type, registering it with the registries
+/// named by its attributes when `register` is called:
+///
+/// - `#[handle_traits(Drawable, Updatable)]` registers casts to
+///   `dyn Drawable` and `dyn Updatable`, for [query].
+/// - `#[handle_clone]` registers the type's [Clone] impl, for [deep_clone].
+/// - `#[handle_provide]` registers the type's `Provide` impl, for [request].
+///
+/// Generic types are not supported, since each instantiation would need
+/// registering separately.
+///
+/// ```
+/// use any_handle::{AnyHandle, Any, Handleable};
+///
+/// trait Drawable { fn draw(&self) -> String; }
+/// trait Named { fn name(&self) -> &str; }
+///
+/// #[derive(Clone, Handleable)]
+/// #[handle_traits(Drawable, Named)]
+/// #[handle_clone]
+/// struct Circle { radius: u32 }
+///
+/// impl Drawable for Circle {
+///     fn draw(&self) -> String { format!("circle of radius {}", self.radius) }
+/// }
+/// impl Named for Circle {
+///     fn name(&self) -> &str { "circle" }
+/// }
+///
+/// Circle::register();
+/// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(Circle { radius: 2 }).upcast();
+/// assert_eq!(handle.query::<dyn Drawable>().unwrap().read().draw(), "circle of radius 2");
+/// assert_eq!(handle.query::<dyn Named>().unwrap().read().name(), "circle");
+/// assert!(handle.deep_clone().is_some());
+/// ```
+///
+/// [query]: https://docs.rs/any_handle/latest/any_handle/struct.AnyHandle.html#method.query
+/// [deep_clone]: https://docs.rs/any_handle/latest/any_handle/struct.AnyHandle.html#method.deep_clone
+/// [request]: https://docs.rs/any_handle/latest/any_handle/struct.AnyHandle.html#method.request
+#[proc_macro_derive(Handleable, attributes(handle_traits, handle_clone, handle_provide))]
+pub fn derive_handleable(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(message) => format!("compile_error!({message:?});").parse().unwrap(),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let mut attributes = Vec::new();
+    let mut tokens = input.into_iter().peekable();
+    let name = loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == '#' => {
+                if let Some(TokenTree::Group(attribute)) = tokens.next() {
+                    attributes.push(attribute.stream());
+                }
+            }
+            Some(TokenTree::Ident(keyword)) if matches!(&*keyword.to_string(), "struct" | "enum" | "union") => {
+                match tokens.next() {
+                    Some(TokenTree::Ident(name)) => break name.to_string(),
+                    _ => return Err("expected a type name".into()),
+                }
+            }
+            Some(_) => {}
+            None => return Err("Handleable can only be derived for structs, enums and unions".into()),
+        }
+    };
+    if matches!(tokens.peek(), Some(TokenTree::Punct(punct)) if punct.as_char() == '<') {
+        return Err("Handleable cannot be derived for generic types".into());
+    }
+
+    let mut body = String::new();
+    for attribute in attributes {
+        body += &registrations(&name, attribute)?;
+    }
+    let output = format!("impl ::any_handle::Handleable for {name} {{ fn register() {{ {body} }} }}");
+    output.parse().map_err(|_| "failed to generate the Handleable impl".into())
+}
+
+/// Turn one of the type's attributes into registration statements for the
+/// type `name`, ignoring attributes which belong to other macros.
+fn registrations(name: &str, attribute: TokenStream) -> Result<String, String> {
+    let mut tokens = attribute.into_iter();
+    let Some(TokenTree::Ident(attribute)) = tokens.next() else { return Ok(String::new()) };
+    match &*attribute.to_string() {
+        "handle_clone" => Ok(format!("::any_handle::register_clone::<{name}>();")),
+        "handle_provide" => Ok(format!("::any_handle::register_provider::<{name}>();")),
+        "handle_traits" => match tokens.next() {
+            Some(TokenTree::Group(traits)) if traits.delimiter() == Delimiter::Parenthesis => Ok(split_list(traits.stream())
+                .iter()
+                .map(|path| format!("::any_handle::register_cast::<{name}, dyn {path}>(|value| value, |value| value);"))
+                .collect()),
+            _ => Err("expected a list of traits, as in #[handle_traits(Drawable)]".into()),
+        },
+        _ => Ok(String::new()),
+    }
+}
+
+/// Split a comma-separated list of paths, which may have generic arguments.
+fn split_list(list: TokenStream) -> Vec<String> {
+    let mut items = vec![TokenStream::new()];
+    let mut depth = 0;
+    for token in list {
+        if let TokenTree::Punct(punct) = &token {
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    items.push(TokenStream::new());
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        items.last_mut().unwrap().extend([token]);
+    }
+    items.into_iter().filter(|item| !item.is_empty()).map(|item| item.to_string()).collect()
+}
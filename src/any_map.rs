@@ -0,0 +1,211 @@
+use crate::{Any, AnyHandle, BuildTypeIdHasher};
+use std::any::TypeId;
+use std::collections::hash_map::{self, HashMap};
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+/// A map of handles keyed by the [TypeId] of their stored value, holding at
+/// most one handle per type, for service locators and resource stores.
+///
+/// The map itself is mutated through `&mut self`, like a [HashMap]; share
+/// it behind a lock, or use a [ShardedAnyHandleMap](crate::ShardedAnyHandleMap)
+/// when many threads insert concurrently. The typed handles it hands out
+/// may be shared freely.
+///
+/// Keys are hashed with `S`, which defaults to the pass-through
+/// [BuildTypeIdHasher]; any other [BuildHasher] may be supplied through
+/// [with_hasher](Self::with_hasher).
+///
+/// # Example
+/// ```
+/// use any_handle::AnyHandleMap;
+///
+/// struct Time { frame: u64 }
+/// struct Score(u32);
+///
+/// let mut resources = AnyHandleMap::new();
+/// resources.insert_value(Time { frame: 0 });
+/// resources.entry::<Score>().or_insert_with(|| Score(0)).write().0 += 10;
+///
+/// assert_eq!(resources.get::<Time>().unwrap().read().frame, 0);
+/// assert_eq!(resources.get::<Score>().unwrap().read().0, 10);
+/// ```
+#[derive(Clone, Default)]
+pub struct AnyHandleMap<S = BuildTypeIdHasher> {
+    handles: HashMap<TypeId, AnyHandle<dyn Any>, S>,
+}
+
+impl AnyHandleMap {
+    /// Create an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: BuildHasher> AnyHandleMap<S> {
+    /// Create an empty map that hashes keys with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        Self { handles: HashMap::with_hasher(hasher) }
+    }
+
+    /// Insert a handle, keyed by the type of its stored value.
+    /// Returns the handle previously stored for that type, if any.
+    pub fn insert(&mut self, handle: AnyHandle<dyn Any>) -> Option<AnyHandle<dyn Any>> {
+        self.handles.insert(handle.stored_type_id(), handle)
+    }
+
+    /// Wrap `value` in a new handle and insert it.
+    /// Returns the handle previously stored for that type, if any.
+    pub fn insert_value<T: Any + Send + Sync>(&mut self, value: T) -> Option<AnyHandle<T>> {
+        let previous = self.insert(AnyHandle::from_value(value).upcast());
+        previous.map(AnyHandle::cast_unchecked)
+    }
+
+    /// Get a typed handle to the stored value of type `T`, if present.
+    pub fn get<T: 'static>(&self) -> Option<AnyHandle<T>> {
+        let handle = self.handles.get(&TypeId::of::<T>())?;
+        Some(handle.clone().cast_unchecked())
+    }
+
+    /// Remove and return the handle stored for type `T`, if present.
+    pub fn remove<T: 'static>(&mut self) -> Option<AnyHandle<T>> {
+        let handle = self.handles.remove(&TypeId::of::<T>())?;
+        Some(handle.cast_unchecked())
+    }
+
+    /// Check whether a value of type `T` is stored.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.handles.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Get the entry for type `T`, for in-place inspection or insertion.
+    pub fn entry<T: Any + Send + Sync>(&mut self) -> AnyHandleMapEntry<'_, T> {
+        AnyHandleMapEntry(self.handles.entry(TypeId::of::<T>()), PhantomData)
+    }
+
+    /// Iterate over the stored handles, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &AnyHandle<dyn Any>> {
+        self.handles.values()
+    }
+
+    /// Get the number of stored handles.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Check whether the map holds no handles.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Remove all handles from the map.
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
+
+impl<S: BuildHasher> Extend<AnyHandle<dyn Any>> for AnyHandleMap<S> {
+    fn extend<I: IntoIterator<Item = AnyHandle<dyn Any>>>(&mut self, iter: I) {
+        for handle in iter {
+            self.insert(handle);
+        }
+    }
+}
+
+impl<S: BuildHasher + Default> FromIterator<AnyHandle<dyn Any>> for AnyHandleMap<S> {
+    fn from_iter<I: IntoIterator<Item = AnyHandle<dyn Any>>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+/// The slot for type `T` in an [AnyHandleMap], which may or may not be
+/// occupied. Returned by [AnyHandleMap::entry].
+pub struct AnyHandleMapEntry<'a, T>(hash_map::Entry<'a, TypeId, AnyHandle<dyn Any>>, PhantomData<fn(T) -> T>);
+
+impl<'a, T: Any + Send + Sync> AnyHandleMapEntry<'a, T> {
+    /// Get the stored handle, inserting one holding `value` first if absent.
+    pub fn or_insert(self, value: T) -> AnyHandle<T> {
+        self.or_insert_with(|| value)
+    }
+
+    /// Get the stored handle, inserting one holding the result of `init`
+    /// first if absent. `init` is not called if the entry is occupied.
+    pub fn or_insert_with(self, init: impl FnOnce() -> T) -> AnyHandle<T> {
+        self.0
+            .or_insert_with(|| AnyHandle::from_value(init()).upcast())
+            .clone()
+            .cast_unchecked()
+    }
+
+    /// Get the stored handle, inserting one holding `T::default()` first if absent.
+    pub fn or_default(self) -> AnyHandle<T>
+    where
+        T: Default,
+    {
+        self.or_insert_with(T::default)
+    }
+
+    /// Modify the stored value in place, if present.
+    pub fn and_modify(self, modify: impl FnOnce(&mut T)) -> Self {
+        if let hash_map::Entry::Occupied(entry) = &self.0 {
+            let handle: AnyHandle<T> = entry.get().clone().cast_unchecked();
+            modify(&mut handle.write());
+        }
+        self
+    }
+
+    /// Get the stored handle, if present, without inserting anything.
+    pub fn get(&self) -> Option<AnyHandle<T>> {
+        match &self.0 {
+            hash_map::Entry::Occupied(entry) => Some(entry.get().clone().cast_unchecked()),
+            hash_map::Entry::Vacant(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counter { count: u32 }
+    struct Name { name: &'static str }
+
+    #[test]
+    fn insert_get_remove() {
+        let mut map = AnyHandleMap::new();
+        assert!(map.insert_value(Counter { count: 1 }).is_none());
+        map.insert(AnyHandle::new(Box::new(Name { name: "audio" })));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get::<Name>().unwrap().read().name, "audio");
+        assert!(map.get::<u8>().is_none());
+
+        assert_eq!(map.insert_value(Counter { count: 2 }).unwrap().read().count, 1);
+        assert_eq!(map.remove::<Counter>().unwrap().read().count, 2);
+        assert!(!map.contains::<Counter>());
+        assert_eq!(map.iter().count(), 1);
+    }
+
+    #[test]
+    fn entry_api() {
+        let mut map = AnyHandleMap::new();
+        assert!(map.entry::<Counter>().get().is_none());
+        map.entry::<Counter>().and_modify(|counter| counter.count += 1).or_default();
+        assert_eq!(map.get::<Counter>().unwrap().read().count, 0);
+
+        map.entry::<Counter>().and_modify(|counter| counter.count += 1).or_insert(Counter { count: 9 });
+        assert_eq!(map.entry::<Counter>().get().unwrap().read().count, 1);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        let mut map = AnyHandleMap::with_hasher(std::collections::hash_map::RandomState::new());
+        map.insert_value(Name { name: "input" });
+        assert_eq!(map.get::<Name>().unwrap().read().name, "input");
+
+        let copy: AnyHandleMap<std::collections::hash_map::RandomState> = map.iter().cloned().collect();
+        assert!(copy.contains::<Name>());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{Any, AnyHandle, HandleIteratorExt};
+use std::any::TypeId;
+
+/// A vector of handles to values of any type, with typed access helpers.
+///
+/// # Example
+/// ```
+/// use any_handle::AnyVec;
+///
+/// struct Enemy { health: u32 }
+///
+/// let mut scene = AnyVec::new();
+/// scene.push_value(Enemy { health: 3 });
+/// scene.push_value("a label");
+/// scene.push_value(Enemy { health: 0 });
+///
+/// scene.retain_of::<Enemy>(|enemy| enemy.health > 0);
+/// assert_eq!(scene.len(), 2);
+/// assert_eq!(scene.iter_of::<Enemy>().count(), 1);
+/// assert_eq!(*scene.get_as::<&str>(1).unwrap().read(), "a label");
+/// ```
+#[derive(Clone, Default)]
+pub struct AnyVec {
+    handles: Vec<AnyHandle<dyn Any>>,
+}
+
+impl AnyVec {
+    /// Create an empty vector.
+    pub fn new() -> Self {
+        Self { handles: Vec::new() }
+    }
+
+    /// Append a handle to the end of the vector.
+    pub fn push(&mut self, handle: AnyHandle<dyn Any>) {
+        self.handles.push(handle);
+    }
+
+    /// Wrap `value` in a new handle and append it, returning a typed handle to it.
+    pub fn push_value<T: Any + Send + Sync>(&mut self, value: T) -> AnyHandle<T> {
+        let handle = AnyHandle::from_value(value);
+        self.handles.push(handle.clone().upcast());
+        handle
+    }
+
+    /// Get the handle at `index`, if in bounds.
+    pub fn get(&self, index: usize) -> Option<&AnyHandle<dyn Any>> {
+        self.handles.get(index)
+    }
+
+    /// Get a typed handle to the value at `index`, if in bounds and of type `T`.
+    pub fn get_as<T: 'static>(&self, index: usize) -> Option<AnyHandle<T>> {
+        self.handles.get(index)?.clone().downcast().ok()
+    }
+
+    /// Iterate over every handle in order.
+    pub fn iter(&self) -> std::slice::Iter<'_, AnyHandle<dyn Any>> {
+        self.handles.iter()
+    }
+
+    /// Iterate over typed handles to the values of type `T`, in order.
+    pub fn iter_of<T: 'static>(&self) -> impl Iterator<Item = AnyHandle<T>> + '_ {
+        self.handles.iter().cloned().filter_downcast()
+    }
+
+    /// Remove the values of type `T` for which `keep` returns `false`.
+    /// Values of other types are always kept.
+    pub fn retain_of<T: 'static>(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        self.handles.retain(|handle| {
+            if handle.stored_type_id() != TypeId::of::<T>() {
+                return true;
+            }
+            keep(&handle.clone().cast_unchecked::<T>().read())
+        });
+    }
+
+    /// Remove the first handle that is a clone of `handle`, replacing it with the
+    /// last element. Returns the removed handle, or [None] if it was not present.
+    pub fn swap_remove<T: ?Sized>(&mut self, handle: &AnyHandle<T>) -> Option<AnyHandle<dyn Any>> {
+        let index = self.handles.iter().position(|member| member.ptr_eq(handle))?;
+        Some(self.handles.swap_remove(index))
+    }
+
+    /// Count the handles in the vector.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Check whether the vector holds no handles.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+
+    /// Remove every handle.
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+}
+
+impl Extend<AnyHandle<dyn Any>> for AnyVec {
+    fn extend<I: IntoIterator<Item = AnyHandle<dyn Any>>>(&mut self, iter: I) {
+        self.handles.extend(iter);
+    }
+}
+
+impl FromIterator<AnyHandle<dyn Any>> for AnyVec {
+    fn from_iter<I: IntoIterator<Item = AnyHandle<dyn Any>>>(iter: I) -> Self {
+        Self { handles: iter.into_iter().collect() }
+    }
+}
+
+impl IntoIterator for AnyVec {
+    type Item = AnyHandle<dyn Any>;
+    type IntoIter = std::vec::IntoIter<AnyHandle<dyn Any>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.handles.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AnyVec {
+    type Item = &'a AnyHandle<dyn Any>;
+    type IntoIter = std::slice::Iter<'a, AnyHandle<dyn Any>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.handles.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_access() {
+        let mut list = AnyVec::new();
+        list.push_value(1u8);
+        list.push_value("two");
+        list.push_value(3u8);
+
+        assert!(list.get_as::<u8>(1).is_none());
+        assert_eq!(*list.get_as::<u8>(2).unwrap().read(), 3);
+        assert_eq!(list.iter_of::<u8>().map(|n| *n.read()).sum::<u8>(), 4);
+
+        list.retain_of::<u8>(|n| *n > 1);
+        assert_eq!(list.len(), 2);
+        assert!(list.get_as::<&str>(0).is_some());
+    }
+
+    #[test]
+    fn swap_remove_by_identity() {
+        let mut list = AnyVec::new();
+        let first = list.push_value(0u8);
+        list.push_value(0u8);
+        list.push_value(1u8);
+
+        assert!(list.swap_remove(&first).is_some());
+        assert!(list.swap_remove(&first).is_none());
+        assert_eq!(*list.get_as::<u8>(0).unwrap().read(), 1);
+    }
+}
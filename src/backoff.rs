@@ -0,0 +1,110 @@
+use crate::{AnyHandle, AnyHandleWriteGuard};
+use std::hash::{BuildHasher, RandomState};
+use std::sync::TryLockError;
+use std::time::{Duration, Instant};
+
+/// Controls how [write_with_backoff](AnyHandle::write_with_backoff) retries
+/// a contended lock before falling back to blocking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BackoffPolicy {
+    /// The delay after the first failed attempt.
+    pub initial_delay: Duration,
+    /// The longest delay between two attempts.
+    pub max_delay: Duration,
+    /// How long to keep retrying before blocking on the lock.
+    pub budget: Duration,
+    /// Whether to randomize each delay by up to half its length, so that
+    /// contending threads don't retry in lockstep.
+    pub jitter: bool,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_micros(1),
+            max_delay: Duration::from_micros(500),
+            budget: Duration::from_millis(5),
+            jitter: true,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Compute the delay to wait after `attempt` failed attempts.
+    fn delay(&self, attempt: u32, seed: u64) -> Duration {
+        let delay = self.initial_delay.saturating_mul(1 << attempt.min(20)).min(self.max_delay);
+        if !self.jitter {
+            return delay;
+        }
+        // A cheap xorshift keeps the jitter free of dependencies.
+        let mut x = seed ^ u64::from(attempt).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        let half = delay / 2;
+        half + Duration::from_nanos(x % (half.as_nanos() as u64 + 1))
+    }
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Get a write guard, retrying a contended lock with exponential backoff.
+    ///
+    /// Non-blocking attempts are made with increasing delays between them, as
+    /// configured by `policy`. Once its budget is spent, this blocks on the
+    /// lock just like [write](AnyHandle::write).
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [write](AnyHandle::write).
+    pub fn write_with_backoff(&self, policy: BackoffPolicy) -> AnyHandleWriteGuard<'_, T> {
+        let start = Instant::now();
+        let seed = RandomState::new().hash_one(start);
+        let mut attempt = 0;
+        let guard = loop {
+            match self.0.try_write() {
+                Ok(guard) => break guard,
+                Err(TryLockError::Poisoned(poisoned)) => panic!("{poisoned}"),
+                Err(TryLockError::WouldBlock) => {}
+            }
+            let delay = policy.delay(attempt, seed);
+            if start.elapsed() + delay > policy.budget {
+                break self.0.write().unwrap();
+            }
+            std::thread::sleep(delay);
+            attempt += 1;
+        };
+        AnyHandleWriteGuard::acquire(guard).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_grow_up_to_the_maximum() {
+        let policy = BackoffPolicy { jitter: false, ..BackoffPolicy::default() };
+        assert_eq!(policy.delay(0, 0), policy.initial_delay);
+        assert_eq!(policy.delay(3, 0), policy.initial_delay * 8);
+        assert_eq!(policy.delay(30, 0), policy.max_delay);
+
+        let jittered = BackoffPolicy::default().delay(4, 42);
+        assert!(jittered >= policy.initial_delay * 8 && jittered <= policy.initial_delay * 16);
+    }
+
+    #[test]
+    fn acquires_contended_lock() {
+        let handle = AnyHandle::new(Box::new(0u32)).downcast::<u32>().ok().unwrap();
+        let reader = handle.clone();
+        let (locked, wait) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                let _guard = reader.read();
+                locked.send(()).unwrap();
+                std::thread::sleep(Duration::from_millis(2));
+            });
+            wait.recv().unwrap();
+            *handle.write_with_backoff(BackoffPolicy::default()) += 1;
+        });
+        assert_eq!(*handle.read(), 1);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::watch::SlotWriteGuard;
+use crate::{Any, AnyHandle, AnyHandleWriteGuard, BuildTypeIdHasher, Contents, Erased};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+type CloneFn = fn(&Erased) -> Box<Erased>;
+
+static CLONERS: RwLock<HashMap<TypeId, CloneFn, BuildTypeIdHasher>> = RwLock::new(HashMap::with_hasher(BuildTypeIdHasher::new()));
+
+fn clone_erased<T: Any + Clone + Send + Sync>(value: &Erased) -> Box<Erased> {
+    Box::new(value.downcast_ref::<T>().expect("cloner invoked on the wrong type").clone())
+}
+
+/// Register `T` as cloneable, so that erased handles holding a `T` can be
+/// duplicated with [deep_clone](AnyHandle::deep_clone).
+pub fn register_clone<T: Any + Clone + Send + Sync>() {
+    CLONERS.write().unwrap().insert(TypeId::of::<T>(), clone_erased::<T>);
+}
+
+impl<T: Any + Clone + Send + Sync> AnyHandle<T> {
+    /// Get a write guard to an object only this handle refers to, first
+    /// moving this handle to a clone of the object if any other handle,
+    /// strong or [weak](AnyHandle::downgrade), refers to it. This mirrors
+    /// [Arc::make_mut]: other holders keep observing the value they had.
+    /// A [read-only](AnyHandle::is_read_only) object is copied too, even
+    /// when unshared, so the guard can always be written through.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let mut config = AnyHandle::from_value(vec!["a"]);
+    /// let snapshot = config.clone();
+    ///
+    /// config.make_mut().push("b");
+    /// assert_eq!(*snapshot.read(), ["a"]);
+    /// assert!(!config.ptr_eq(&snapshot));
+    ///
+    /// let unique = config.clone();
+    /// drop(unique);
+    /// config.make_mut().push("c");
+    /// assert_eq!(config.read().len(), 3);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [write](AnyHandle::write).
+    pub fn make_mut(&mut self) -> AnyHandleWriteGuard<'_, T> {
+        if Arc::get_mut(&mut self.0).is_none() || self.is_read_only() {
+            let value = self.read().clone();
+            *self = AnyHandle::from_value(value);
+        }
+        self.write()
+    }
+
+    /// Make a new handle to a copy of the object, independent of this one,
+    /// unlike [clone](Clone::clone) which shares the object.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    pub fn deep_clone(&self) -> AnyHandle<T> {
+        AnyHandle::from_value(self.read().clone())
+    }
+
+    /// Make a new handle to a copy of the object, so that edits through it
+    /// don't affect the holders of this one. This is the same as
+    /// [deep_clone](AnyHandle::deep_clone).
+    #[inline(always)]
+    pub fn detach(&self) -> AnyHandle<T> {
+        self.deep_clone()
+    }
+}
+
+impl AnyHandle<dyn Any> {
+    /// Make a new handle to a copy of the object, independent of this one.
+    ///
+    /// The object is copied with the function registered for its type by
+    /// [register_clone], or the one given to [borrowed](AnyHandle::borrowed).
+    /// Returns [None] if there is neither, or if the handle has been revoked
+    /// or [poisoned](AnyHandle::is_poisoned).
+    ///
+    /// ```
+    /// use any_handle::{register_clone, AnyHandle, Any};
+    ///
+    /// #[derive(Clone)]
+    /// struct Board { cells: Vec<u8> }
+    ///
+    /// let original: AnyHandle<dyn Any> = AnyHandle::from_value(Board { cells: vec![0; 9] }).upcast();
+    /// assert!(original.deep_clone().is_none());
+    ///
+    /// register_clone::<Board>();
+    /// let copy: AnyHandle<Board> = original.deep_clone().unwrap().downcast().ok().unwrap();
+    /// copy.write().cells[4] = 1;
+    /// assert_eq!(original.downcast::<Board>().ok().unwrap().read().cells[4], 0);
+    /// ```
+    pub fn deep_clone(&self) -> Option<AnyHandle<dyn Any>> {
+        let slot = self.0.read().ok()?;
+        let clone = match slot.value {
+            Contents::Static(_, Some(promote)) => promote,
+            Contents::Forked(_, copy) => copy,
+            Contents::Revoked => return None,
+            _ => *CLONERS.read().unwrap().get(&slot.type_id)?,
+        };
+        Some(AnyHandle::from_contents(Contents::Owned(clone(slot.value.get())), slot.type_id, slot.type_name))
+    }
+
+    /// Make a new handle to a copy of the object, as
+    /// [deep_clone](AnyHandle::deep_clone) does, with the same requirements.
+    #[inline(always)]
+    pub fn detach(&self) -> Option<AnyHandle<dyn Any>> {
+        self.deep_clone()
+    }
+
+    /// Make a new handle which shares the object with this one until either
+    /// of them is written to, when the writer copies it, for
+    /// [HandleStore::fork](crate::HandleStore::fork).
+    ///
+    /// Returns [None] if the object cannot be copied, for the same reasons as
+    /// [deep_clone](AnyHandle::deep_clone), or is [lazy](AnyHandle::new_lazy)
+    /// and has not been produced yet.
+    pub(crate) fn fork(&self) -> Option<AnyHandle<dyn Any>> {
+        let mut slot = SlotWriteGuard::new(self.0.write().ok()?);
+        let contents = match &slot.value {
+            Contents::Static(value, promote) => Contents::Static(*value, *promote),
+            Contents::Forked(value, copy) => Contents::Forked(value.clone(), *copy),
+            Contents::Lazy(value, _) if value.get().is_none() => return None,
+            Contents::Revoked => return None,
+            Contents::Owned(_) | Contents::Lazy(..) => {
+                let copy = *CLONERS.read().unwrap().get(&slot.type_id)?;
+                slot.value.initialize();
+                let Contents::Owned(value) = std::mem::replace(&mut slot.value, Contents::Revoked) else {
+                    unreachable!();
+                };
+                let value = Arc::<Erased>::from(value);
+                // The version is unchanged, as the object itself is.
+                slot.value = Contents::Forked(value.clone(), copy);
+                Contents::Forked(value, copy)
+            }
+        };
+        Some(AnyHandle::from_contents(contents, slot.type_id, slot.type_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deep_clones_are_independent() {
+        let mut original = AnyHandle::from_value(vec![1u8]);
+        let copy = original.deep_clone();
+        copy.write().push(2);
+        assert_eq!(original.make_mut().len(), 1);
+        assert_eq!(*copy.detach().read(), [1, 2]);
+
+        #[derive(Clone)]
+        struct Defaults { level: u8 }
+        static DEFAULTS: Defaults = Defaults { level: 3 };
+        let borrowed = AnyHandle::borrowed(&DEFAULTS);
+        assert_eq!(borrowed.deep_clone().unwrap().downcast::<Defaults>().ok().unwrap().read().level, 3);
+        assert!(AnyHandle::from_static(&DEFAULTS).detach().is_none());
+
+        static LEVELS: [u8; 2] = [1, 2];
+        let mut levels = AnyHandle::from_static(&LEVELS).downcast::<[u8; 2]>().ok().unwrap();
+        levels.make_mut()[0] = 5;
+        assert!(!levels.is_read_only());
+        assert_eq!((*levels.read(), LEVELS), ([5, 2], [1, 2]));
+    }
+
+    #[test]
+    fn weak_references_keep_the_old_value() {
+        let mut handle = AnyHandle::from_value(1u32);
+        let weak = handle.downgrade();
+
+        *handle.make_mut() = 2;
+        assert!(weak.upgrade().is_none());
+        assert_eq!(*handle.read(), 2);
+    }
+}
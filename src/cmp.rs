@@ -0,0 +1,62 @@
+use crate::{lock_many, AnyHandle};
+use std::cmp::Ordering;
+
+impl<T: 'static> AnyHandle<T> {
+    /// Lock both objects, in the same order as [lock_many], and apply `f` to
+    /// their values. An object compared with itself is only locked once.
+    fn compare<R>(&self, other: &Self, f: impl FnOnce(&T, &T) -> R) -> R {
+        if self.ptr_eq(other) {
+            let value = self.read();
+            return f(&value, &value);
+        }
+        let (a, b) = lock_many((self, other));
+        f(&a, &b)
+    }
+}
+
+/// Compare handles by the values of their objects, locking both for reading.
+///
+/// # Panics
+/// Panics under the same conditions as [read](AnyHandle::read).
+impl<T: PartialEq + 'static> PartialEq for AnyHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.compare(other, T::eq)
+    }
+}
+
+impl<T: Eq + 'static> Eq for AnyHandle<T> {}
+
+/// Order handles by the values of their objects, locking both for reading.
+///
+/// # Panics
+/// Panics under the same conditions as [read](AnyHandle::read).
+impl<T: PartialOrd + 'static> PartialOrd for AnyHandle<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.compare(other, T::partial_cmp)
+    }
+}
+
+impl<T: Ord + 'static> Ord for AnyHandle<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other, T::cmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_values() {
+        let a = AnyHandle::from_value(1u32);
+        let b = AnyHandle::from_value(1u32);
+        assert!(a == b && a == a.clone());
+
+        let mut sorted = [AnyHandle::from_value(3), a, AnyHandle::from_value(2)];
+        sorted.sort();
+        assert_eq!(sorted.iter().map(|n| *n.read()).collect::<Vec<_>>(), [1, 2, 3]);
+
+        let nan = AnyHandle::from_value(f64::NAN);
+        assert!(nan != nan.clone());
+    }
+}
@@ -0,0 +1,276 @@
+use crate::watch::SlotWriteGuard;
+use crate::{AnyHandle, Erased, Shared};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Implemented by types stored in handles that may hold other handles, so
+/// that a [CycleCollector] can find and break reference cycles among them.
+pub trait Trace {
+    /// Report every handle directly held by this value to `tracer`.
+    fn trace(&self, tracer: &mut Tracer);
+
+    /// Drop every handle held by this value. Called on values found to be
+    /// part of an unreachable cycle, to break it.
+    fn clear(&mut self);
+}
+
+/// Collects the handles reported by [Trace::trace].
+pub struct Tracer {
+    edges: Vec<usize>,
+}
+
+impl Tracer {
+    /// Report a handle held by the value being traced.
+    pub fn visit<T: ?Sized>(&mut self, handle: &AnyHandle<T>) {
+        self.edges.push(Arc::as_ptr(&handle.0) as usize);
+    }
+}
+
+struct Tracked {
+    shared: Weak<Shared>,
+    trace: fn(&Erased, &mut Tracer),
+    clear: fn(&mut Erased),
+}
+
+/// An opt-in collector for reference cycles between handles.
+///
+/// Handles whose values implement [Trace] are registered with
+/// [track](Self::track). [collect](Self::collect) then works out which
+/// tracked handles are only kept alive by references from other tracked
+/// handles, and calls [Trace::clear] on them, so that the cycles are broken
+/// and the values dropped.
+///
+/// Tracking does not keep a handle alive. A handle that is locked while
+/// collecting is conservatively treated as reachable.
+///
+/// Garbage is only reachable through [weak handles](crate::WeakAnyHandle),
+/// so the collector locks every value it is about to clear and checks that
+/// no new strong handle to it has appeared since it was counted. An
+/// [upgrade](crate::WeakAnyHandle::upgrade) which races the clearing itself
+/// may still succeed, and then observes the cleared value; don't upgrade weak
+/// handles to values which may be garbage while [collect](Self::collect) runs.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, CycleCollector, Trace, Tracer};
+///
+/// struct Node { next: Option<AnyHandle<Node>> }
+///
+/// impl Trace for Node {
+///     fn trace(&self, tracer: &mut Tracer) {
+///         if let Some(next) = &self.next {
+///             tracer.visit(next);
+///         }
+///     }
+///
+///     fn clear(&mut self) {
+///         self.next = None;
+///     }
+/// }
+///
+/// let collector = CycleCollector::new();
+/// let a: AnyHandle<Node> = AnyHandle::new(Box::new(Node { next: None })).downcast().ok().unwrap();
+/// let b: AnyHandle<Node> = AnyHandle::new(Box::new(Node { next: Some(a.clone()) })).downcast().ok().unwrap();
+/// a.write().next = Some(b.clone());
+/// collector.track(&a);
+/// collector.track(&b);
+///
+/// drop(b);
+/// assert_eq!(collector.collect(), 0);
+///
+/// drop(a);
+/// assert_eq!(collector.collect(), 2);
+/// assert_eq!(collector.tracked(), 0);
+/// ```
+#[derive(Default)]
+pub struct CycleCollector {
+    tracked: Mutex<Vec<Tracked>>,
+}
+
+impl CycleCollector {
+    /// Create a collector that tracks no handles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start tracking `handle` for cycle collection. Tracking a handle which
+    /// is already tracked, or a clone of it, has no effect.
+    pub fn track<T: Trace + 'static>(&self, handle: &AnyHandle<T>) {
+        fn trace<T: Trace + 'static>(value: &Erased, tracer: &mut Tracer) {
+            value.downcast_ref::<T>().unwrap().trace(tracer)
+        }
+        fn clear<T: Trace + 'static>(value: &mut Erased) {
+            value.downcast_mut::<T>().unwrap().clear()
+        }
+        let mut tracked = self.tracked.lock().unwrap();
+        // The tracked weak reference keeps the allocation, and so its address,
+        // from being reused.
+        if tracked.iter().any(|entry| entry.shared.as_ptr() == Arc::as_ptr(&handle.0)) {
+            return;
+        }
+        tracked.push(Tracked {
+            shared: Arc::downgrade(&handle.0),
+            trace: trace::<T>,
+            clear: clear::<T>,
+        });
+    }
+
+    /// Count the tracked handles that are still alive.
+    pub fn tracked(&self) -> usize {
+        let mut tracked = self.tracked.lock().unwrap();
+        tracked.retain(|entry| entry.shared.strong_count() > 0);
+        tracked.len()
+    }
+
+    /// Find the tracked handles that are unreachable except through cycles,
+    /// and clear them. Returns the number of values cleared.
+    pub fn collect(&self) -> usize {
+        let mut tracked = self.tracked.lock().unwrap();
+        tracked.retain(|entry| entry.shared.strong_count() > 0);
+
+        let live: Vec<_> = tracked.iter().filter_map(|entry| Some((entry.shared.upgrade()?, entry))).collect();
+        let index: HashMap<usize, usize> =
+            live.iter().enumerate().map(|(i, (shared, _))| (Arc::as_ptr(shared) as usize, i)).collect();
+
+        // Trace every value, counting the references that come from tracked values.
+        let mut internal = vec![0; live.len()];
+        let mut edges = vec![Vec::new(); live.len()];
+        let mut reachable = vec![false; live.len()];
+        for (i, (shared, entry)) in live.iter().enumerate() {
+            let Ok(slot) = shared.try_read() else {
+                reachable[i] = true;
+                continue;
+            };
+            if slot.value.is_revoked() {
+                continue;
+            }
+            let mut tracer = Tracer { edges: Vec::new() };
+            (entry.trace)(slot.value.get(), &mut tracer);
+            edges[i] = tracer.edges.iter().filter_map(|address| index.get(address).copied()).collect();
+            for &j in &edges[i] {
+                internal[j] += 1;
+            }
+        }
+
+        // Anything referenced from outside the tracked set (besides our own
+        // upgraded reference) is a root; everything reachable from a root lives.
+        let mut pending: Vec<usize> = (0..live.len())
+            .filter(|&i| reachable[i] || Arc::strong_count(&live[i].0) - 1 > internal[i])
+            .collect();
+        for &i in &pending {
+            reachable[i] = true;
+        }
+        while let Some(i) = pending.pop() {
+            for &j in &edges[i] {
+                if !reachable[j] {
+                    reachable[j] = true;
+                    pending.push(j);
+                }
+            }
+        }
+
+        // Lock all of the garbage before clearing any of it. If any of it is
+        // locked, or has gained a strong handle since it was counted, a weak
+        // handle was upgraded to it, so nothing is cleared this time.
+        let mut garbage = Vec::new();
+        for (i, (shared, entry)) in live.iter().enumerate() {
+            if reachable[i] {
+                continue;
+            }
+            match shared.try_write() {
+                Ok(slot) if Arc::strong_count(shared) - 1 == internal[i] => garbage.push((SlotWriteGuard::new(slot), entry)),
+                _ => {
+                    garbage.clear();
+                    break;
+                }
+            }
+        }
+
+        let mut collected = 0;
+        for (mut slot, entry) in garbage {
+            if !slot.value.is_read_only() && !slot.value.is_revoked() {
+                slot.version += 1;
+                (entry.clear)(slot.value.get_mut());
+                collected += 1;
+            }
+        }
+        drop(live);
+        tracked.retain(|entry| entry.shared.strong_count() > 0);
+        collected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node {
+        next: Vec<AnyHandle<Node>>,
+    }
+
+    impl Trace for Node {
+        fn trace(&self, tracer: &mut Tracer) {
+            self.next.iter().for_each(|next| tracer.visit(next));
+        }
+
+        fn clear(&mut self) {
+            self.next.clear();
+        }
+    }
+
+    fn node(next: Vec<AnyHandle<Node>>) -> AnyHandle<Node> {
+        AnyHandle::from_value(Node { next })
+    }
+
+    #[test]
+    fn self_cycles_are_collected() {
+        let collector = CycleCollector::new();
+        let looped = node(Vec::new());
+        looped.write().next.push(looped.clone());
+        collector.track(&looped);
+        let weak = looped.downgrade();
+
+        assert_eq!(collector.collect(), 0);
+        drop(looped);
+        assert_eq!(collector.collect(), 1);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn rooted_cycles_are_kept() {
+        let collector = CycleCollector::new();
+        let leaf = node(Vec::new());
+        let a = node(vec![leaf.clone()]);
+        let b = node(vec![a.clone()]);
+        a.write().next.push(b.clone());
+        for handle in [&leaf, &a, &b] {
+            collector.track(handle);
+        }
+
+        let root = a.clone();
+        drop((leaf, a, b));
+        assert_eq!(collector.collect(), 0);
+        assert_eq!(root.read().next.len(), 2);
+        assert_eq!(root.read().next[1].read().next.len(), 1);
+        assert_eq!(collector.tracked(), 3);
+
+        drop(root);
+        assert_eq!(collector.collect(), 3);
+        assert_eq!(collector.tracked(), 0);
+    }
+
+    #[test]
+    fn tracking_twice_is_harmless() {
+        let collector = CycleCollector::new();
+        let a = node(Vec::new());
+        let b = node(vec![a.clone()]);
+        a.write().next.push(b.clone());
+        collector.track(&a);
+        collector.track(&a.clone());
+        collector.track(&b);
+        assert_eq!(collector.tracked(), 2);
+
+        drop((a, b));
+        assert_eq!(collector.collect(), 2);
+    }
+}
@@ -0,0 +1,102 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+struct Buffers<T> {
+    front: RwLock<Arc<T>>,
+    back: RwLock<T>,
+}
+
+/// A shared, double-buffered value for read-previous/write-next patterns.
+///
+/// Readers see the last committed state through [read_front](Self::read_front),
+/// which returns a cheap snapshot and never waits for writers. Writers work
+/// on a separate back buffer through [write_back](Self::write_back), and
+/// [flip](Self::flip) commits it as the new front.
+///
+/// Clones share the same buffers.
+///
+/// # Example
+/// ```
+/// use any_handle::DoubleBufferedHandle;
+///
+/// let positions = DoubleBufferedHandle::new(vec![0.0_f32; 4]);
+///
+/// let previous = positions.read_front();
+/// for (next, prev) in positions.write_back().iter_mut().zip(previous.iter()) {
+///     *next = prev + 1.0;
+/// }
+/// assert_eq!(positions.read_front()[0], 0.0);
+///
+/// positions.flip();
+/// assert_eq!(positions.read_front()[0], 1.0);
+/// assert_eq!(previous[0], 0.0);
+/// ```
+pub struct DoubleBufferedHandle<T>(Arc<Buffers<T>>);
+
+impl<T: Clone> DoubleBufferedHandle<T> {
+    /// Create a handle whose front and back buffers both start as `value`.
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(Buffers {
+            front: RwLock::new(Arc::new(value.clone())),
+            back: RwLock::new(value),
+        }))
+    }
+
+    /// Commit the back buffer, making a copy of it the new front.
+    ///
+    /// Snapshots taken earlier with [read_front](Self::read_front) keep
+    /// referring to the previous state. The back buffer retains its contents,
+    /// so the next step continues from the state just committed.
+    pub fn flip(&self) {
+        let committed = Arc::new(self.0.back.read().unwrap().clone());
+        *self.0.front.write().unwrap() = committed;
+    }
+}
+
+impl<T> DoubleBufferedHandle<T> {
+    /// Get a snapshot of the last committed state.
+    ///
+    /// This only holds the front lock long enough to clone an [Arc], so it is
+    /// never blocked by a writer holding the back buffer.
+    pub fn read_front(&self) -> Arc<T> {
+        self.0.front.read().unwrap().clone()
+    }
+
+    /// Get read access to the back buffer, as it stands before the next flip.
+    pub fn read_back(&self) -> RwLockReadGuard<'_, T> {
+        self.0.back.read().unwrap()
+    }
+
+    /// Get write access to the back buffer.
+    pub fn write_back(&self) -> RwLockWriteGuard<'_, T> {
+        self.0.back.write().unwrap()
+    }
+}
+
+impl<T> Clone for DoubleBufferedHandle<T> {
+    /// Make a new handle to the same buffers.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn front_is_stable_until_flip() {
+        let handle = DoubleBufferedHandle::new(1);
+        let other = handle.clone();
+
+        let mut back = handle.write_back();
+        *back = 2;
+        assert_eq!(*other.read_front(), 1);
+        drop(back);
+
+        assert_eq!(*other.read_back(), 2);
+        assert_eq!(*other.read_front(), 1);
+        handle.flip();
+        assert_eq!(*other.read_front(), 2);
+    }
+}
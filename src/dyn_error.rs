@@ -0,0 +1,114 @@
+use crate::{Any, AnyHandle, AnyHandleReadGuard, AnyHandleWriteGuard, Contents, Erased, MappedAnyHandleReadGuard};
+use std::any::TypeId;
+use std::error::Error;
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+// Errors are stored as a `Box<dyn Error + Send + Sync>`, and the guards of a
+// handle to `dyn Error` deref through it, like those of `str` and slice handles.
+
+type BoxedError = Box<dyn Error + Send + Sync>;
+
+fn view_error(value: &Erased) -> &(dyn Error + 'static) {
+    &**value.downcast_ref::<BoxedError>().expect("error handle holds a different type")
+}
+
+impl AnyHandle<dyn Error> {
+    /// Initialize a handle to an error, which its guards deref to, so its
+    /// message and source can be read without knowing its type.
+    ///
+    /// The error is stored as a `Box<dyn Error + Send + Sync>`, so the handle
+    /// can be [upcast](AnyHandle::upcast) and later recovered with
+    /// [downcast_dyn_error](AnyHandle::downcast_dyn_error) or
+    /// `downcast::<Box<dyn Error + Send + Sync>>()`.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    /// use std::error::Error;
+    /// use std::io;
+    ///
+    /// let errors: Vec<AnyHandle<dyn Error>> = vec![
+    ///     AnyHandle::from_error("disk full".into()),
+    ///     AnyHandle::from_error(Box::new(io::Error::from(io::ErrorKind::NotFound))),
+    /// ];
+    /// assert_eq!(errors[0].read().to_string(), "disk full");
+    ///
+    /// let not_found = errors[1].downcast_error::<io::Error>().unwrap();
+    /// assert_eq!(not_found.kind(), io::ErrorKind::NotFound);
+    /// assert!(errors[0].downcast_error::<io::Error>().is_none());
+    /// ```
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn from_error(error: BoxedError) -> Self {
+        AnyHandle::from_contents(Contents::Owned(Box::new(error)), TypeId::of::<BoxedError>(), Some(std::any::type_name::<BoxedError>())).cast_unchecked()
+    }
+
+    /// Get a read guard over the error as an `E`, if that is its concrete
+    /// type. Otherwise, returns [None] and releases the lock.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    pub fn downcast_error<E: Error + 'static>(&self) -> Option<MappedAnyHandleReadGuard<'_, E>> {
+        let guard = AnyHandleReadGuard::<dyn Any>(self.read().0, PhantomData);
+        guard.try_map_any(|value| value.downcast_ref::<BoxedError>()?.downcast_ref())
+    }
+}
+
+impl AnyHandle<dyn Any> {
+    /// Downcast this handle to a view of the stored error as a `dyn Error`,
+    /// if it holds a `Box<dyn Error + Send + Sync>`. Otherwise, returns Err(self).
+    pub fn downcast_dyn_error(self) -> Result<AnyHandle<dyn Error>, Self> {
+        if self.is::<BoxedError>() {
+            Ok(self.cast_unchecked())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+// Generate the Deref implementations for both guard types.
+macro_rules! impl_deref_error {
+    ($($Type:ident),+) => {$(
+        impl<'a> Deref for $Type<'a, dyn Error> {
+            type Target = dyn Error;
+
+            #[inline(always)]
+            fn deref(&self) -> &(dyn Error + 'static) {
+                view_error(self.0.value.get())
+            }
+        }
+    )+}
+}
+
+impl_deref_error!(AnyHandleReadGuard, AnyHandleWriteGuard);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt;
+
+    #[derive(Debug)]
+    struct Wrapped(std::num::ParseIntError);
+
+    impl fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "bad config")
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn errors_survive_erasure() {
+        let cause = "x".parse::<u8>().unwrap_err();
+        let handle = AnyHandle::from_error(Box::new(Wrapped(cause.clone())));
+        assert_eq!(handle.read().source().unwrap().to_string(), cause.to_string());
+
+        let handle = handle.upcast().downcast_dyn_error().ok().unwrap();
+        assert_eq!(handle.downcast_error::<Wrapped>().unwrap().0, cause);
+        assert!(AnyHandle::from_value(1u8).upcast().downcast_dyn_error().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{Any, AnyHandle, Quota};
+use std::any::TypeId;
+use std::error::Error;
+use std::fmt;
+
+/// Returned by [write_if_version](crate::AnyHandle::write_if_version) when the
+/// object has been written to since the caller observed it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VersionConflict {
+    /// The version the caller expected.
+    pub expected: u64,
+    /// The version the object actually had.
+    pub actual: u64,
+}
+
+impl fmt::Display for VersionConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "handle was modified: expected version {}, found {}", self.expected, self.actual)
+    }
+}
+
+impl Error for VersionConflict {}
+
+/// Returned by [write_if_version_checked](crate::AnyHandle::write_if_version_checked)
+/// when the object has been written to since the caller observed it, or
+/// cannot be written to at all.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteIfVersionError {
+    /// The object has been written to since the expected version.
+    Conflict(VersionConflict),
+    /// The object could not be accessed.
+    Access(AccessError),
+}
+
+impl From<VersionConflict> for WriteIfVersionError {
+    fn from(conflict: VersionConflict) -> Self {
+        WriteIfVersionError::Conflict(conflict)
+    }
+}
+
+impl From<AccessError> for WriteIfVersionError {
+    fn from(error: AccessError) -> Self {
+        WriteIfVersionError::Access(error)
+    }
+}
+
+impl fmt::Display for WriteIfVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteIfVersionError::Conflict(conflict) => conflict.fmt(f),
+            WriteIfVersionError::Access(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for WriteIfVersionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteIfVersionError::Conflict(conflict) => Some(conflict),
+            WriteIfVersionError::Access(error) => Some(error),
+        }
+    }
+}
+
+/// Describes why an object could not be accessed through a handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AccessError {
+    /// The object was invalidated with [revoke](crate::AnyHandle::revoke).
+    Revoked,
+    /// A write was attempted on a read-only object; see
+    /// [is_read_only](crate::AnyHandle::is_read_only).
+    ReadOnly,
+    /// The object is locked, and a non-blocking method such as
+    /// [try_read](crate::AnyHandle::try_read) was used.
+    WouldBlock,
+    /// The object stayed locked for the whole timeout given to a method such
+    /// as [read_timeout](crate::AnyHandle::read_timeout).
+    TimedOut,
+    /// A thread panicked while writing to the object; see
+    /// [is_poisoned](crate::AnyHandle::is_poisoned).
+    Poisoned,
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessError::Revoked => f.write_str("handle has been revoked"),
+            AccessError::ReadOnly => f.write_str("cannot write to a read-only handle"),
+            AccessError::WouldBlock => f.write_str("handle is locked"),
+            AccessError::TimedOut => f.write_str("timed out waiting for handle"),
+            AccessError::Poisoned => f.write_str("handle was poisoned by a panicking writer"),
+        }
+    }
+}
+
+impl Error for AccessError {}
+
+/// Returned by [downcast_verbose](crate::AnyHandle::downcast_verbose) when the
+/// stored value is not of the requested type.
+pub struct DowncastError {
+    pub(crate) handle: AnyHandle<dyn Any>,
+    pub(crate) expected: &'static str,
+    pub(crate) actual: TypeId,
+    pub(crate) actual_name: Option<&'static str>,
+}
+
+impl DowncastError {
+    /// Get back the handle that could not be downcast.
+    pub fn into_handle(self) -> AnyHandle<dyn Any> {
+        self.handle
+    }
+
+    /// Get the name of the type that was requested.
+    pub fn expected(&self) -> &'static str {
+        self.expected
+    }
+
+    /// Get the [TypeId] of the stored value.
+    pub fn actual_type_id(&self) -> TypeId {
+        self.actual
+    }
+
+    /// Get the name of the stored value's type. This is only known for
+    /// handles created from a concrete type, such as with
+    /// [from_value](crate::AnyHandle::from_value), rather than from an
+    /// already-erased box.
+    pub fn actual_name(&self) -> Option<&'static str> {
+        self.actual_name
+    }
+}
+
+impl fmt::Debug for DowncastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DowncastError")
+            .field("expected", &self.expected)
+            .field("actual", &self.actual)
+            .field("actual_name", &self.actual_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for DowncastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual_name {
+            Some(actual) => write!(f, "cannot downcast a handle holding `{actual}` to `{}`", self.expected),
+            None => write!(f, "cannot downcast a handle holding {:?} to `{}`", self.actual, self.expected),
+        }
+    }
+}
+
+impl Error for DowncastError {}
+
+/// Returned by [try_insert](crate::HandleStore::try_insert) when the store
+/// already holds as many handles of the type, or as much memory, as its
+/// [Quota] allows.
+pub struct QuotaExceeded {
+    pub(crate) handle: AnyHandle<dyn Any>,
+    pub(crate) quota: Quota,
+}
+
+impl QuotaExceeded {
+    /// Get back the handle that could not be inserted.
+    pub fn into_handle(self) -> AnyHandle<dyn Any> {
+        self.handle
+    }
+
+    /// Get the quota that would have been exceeded.
+    pub fn quota(&self) -> Quota {
+        self.quota
+    }
+}
+
+impl fmt::Debug for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QuotaExceeded").field("quota", &self.quota).finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.handle.type_name() {
+            Some(name) => write!(f, "store quota exceeded for `{name}`"),
+            None => f.write_str("store quota exceeded"),
+        }
+    }
+}
+
+impl Error for QuotaExceeded {}
@@ -0,0 +1,130 @@
+use crate::{
+    AccessError, Any, AnyHandle, AnyHandleReadGuard, AnyHandleWriteGuard, MappedAnyHandleReadGuard,
+    MappedAnyHandleWriteGuard, OwnedAnyHandleReadGuard, OwnedAnyHandleWriteGuard,
+};
+use std::fmt;
+use std::sync::TryLockError;
+
+/// Formats the value if it can be read without blocking, or a placeholder
+/// such as `<locked>` if it cannot.
+impl<T: fmt::Debug + 'static> fmt::Debug for AnyHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("AnyHandle");
+        match self.try_read() {
+            Ok(value) => debug.field("value", &*value),
+            Err(AccessError::WouldBlock) => debug.field("value", &format_args!("<locked>")),
+            Err(AccessError::Revoked) => debug.field("value", &format_args!("<revoked>")),
+            Err(_) => debug.field("value", &format_args!("<poisoned>")),
+        };
+        debug.finish()
+    }
+}
+
+/// Formats the value itself, as if the handle were not there, if it can be
+/// read without blocking. Otherwise, like the [Debug](fmt::Debug) format,
+/// writes a placeholder such as `<locked>` instead of waiting for the writer.
+///
+/// ```
+/// use any_handle::AnyHandle;
+///
+/// let score = AnyHandle::from_value(42);
+/// assert_eq!(score.to_string(), "42");
+///
+/// let _guard = score.write();
+/// assert_eq!(score.to_string(), "<locked>");
+/// ```
+impl<T: fmt::Display + 'static> fmt::Display for AnyHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.try_read() {
+            Ok(value) => fmt::Display::fmt(&*value, f),
+            Err(AccessError::WouldBlock) => f.write_str("<locked>"),
+            Err(AccessError::Revoked) => f.write_str("<revoked>"),
+            Err(_) => f.write_str("<poisoned>"),
+        }
+    }
+}
+
+/// Formats the stored type name, if known, and the reference count.
+/// Like the typed format, this never blocks.
+impl fmt::Debug for AnyHandle<dyn Any> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let type_name = match self.0.try_read() {
+            Ok(slot) => slot.type_name.unwrap_or("<unknown>"),
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner().type_name.unwrap_or("<unknown>"),
+            Err(TryLockError::WouldBlock) => "<locked>",
+        };
+        f.debug_struct("AnyHandle")
+            .field("type_name", &format_args!("{type_name}"))
+            .field("reference_count", &self.reference_count())
+            .finish()
+    }
+}
+
+macro_rules! impl_debug_guard {
+    ($($Type:ident),+) => {$(
+        impl<T: fmt::Debug + 'static> fmt::Debug for $Type<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&**self, f)
+            }
+        }
+
+        impl<T: fmt::Display + 'static> fmt::Display for $Type<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&**self, f)
+            }
+        }
+    )+};
+}
+
+macro_rules! impl_debug_owned_guard {
+    ($($Type:ident),+) => {$(
+        impl<T: fmt::Debug + 'static> fmt::Debug for $Type<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&**self, f)
+            }
+        }
+    )+};
+}
+
+impl_debug_guard!(AnyHandleReadGuard, AnyHandleWriteGuard);
+impl_debug_owned_guard!(OwnedAnyHandleReadGuard, OwnedAnyHandleWriteGuard);
+
+impl<U: fmt::Debug + ?Sized> fmt::Debug for MappedAnyHandleReadGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<U: fmt::Debug + ?Sized> fmt::Debug for MappedAnyHandleWriteGuard<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_values_and_placeholders() {
+        let handle = AnyHandle::from_value(vec![1, 2]);
+        assert_eq!(format!("{handle:?}"), "AnyHandle { value: [1, 2] }");
+
+        let guard = handle.write();
+        assert_eq!(format!("{guard:?}"), "[1, 2]");
+        assert_eq!(format!("{:?}", guard.map(|values| &mut values[0])), "1");
+
+        let other = handle.clone();
+        let _guard = handle.write();
+        assert_eq!(format!("{other:?}"), "AnyHandle { value: <locked> }");
+        drop(_guard);
+
+        let text = AnyHandle::from_value(String::from("ready"));
+        assert_eq!(format!("[{:>6}] {}", text, text.read()), "[ ready] ready");
+        text.revoke();
+        assert_eq!(text.to_string(), "<revoked>");
+
+        let erased = other.upcast();
+        assert_eq!(format!("{erased:?}"), "AnyHandle { type_name: alloc::vec::Vec<i32>, reference_count: 2 }");
+    }
+}
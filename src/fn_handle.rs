@@ -0,0 +1,81 @@
+use crate::{Any, AnyHandle};
+
+type BoxedFn<Args, R> = Box<dyn Fn(Args) -> R + Send + Sync>;
+
+/// A shared handle to a callable, for building callback registries.
+///
+/// The callable is stored as a `Box<dyn Fn(Args) -> R + Send + Sync>` in an
+/// ordinary handle, so it can be erased into an `AnyHandle<dyn Any>` with
+/// [into_any](Self::into_any) and recovered with [downcast](Self::downcast).
+/// Functions of several arguments take them as a tuple.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, Any, FnHandle};
+///
+/// let add = FnHandle::new(|(a, b): (i32, i32)| a + b);
+/// assert_eq!(add.call((2, 3)), 5);
+///
+/// let erased: AnyHandle<dyn Any> = add.into_any();
+/// let add = FnHandle::<(i32, i32), i32>::downcast(erased).ok().unwrap();
+/// assert_eq!(add.call((4, 5)), 9);
+/// ```
+pub struct FnHandle<Args, R>(AnyHandle<BoxedFn<Args, R>>);
+
+impl<Args: 'static, R: 'static> FnHandle<Args, R> {
+    /// Create a handle to `f`.
+    pub fn new(f: impl Fn(Args) -> R + Send + Sync + 'static) -> Self {
+        let f: BoxedFn<Args, R> = Box::new(f);
+        Self(AnyHandle::from_value(f))
+    }
+
+    /// Call the function.
+    ///
+    /// The handle is read-locked for the duration of the call, so the function
+    /// may be called from many threads at once, but calling
+    /// [replace](Self::replace) from within it will deadlock.
+    pub fn call(&self, args: Args) -> R {
+        (self.0.read())(args)
+    }
+
+    /// Replace the function for every clone of this handle.
+    pub fn replace(&self, f: impl Fn(Args) -> R + Send + Sync + 'static) {
+        *self.0.clone().write() = Box::new(f);
+    }
+
+    /// Erase this handle, so it can be stored alongside handles of other types.
+    pub fn into_any(self) -> AnyHandle<dyn Any> {
+        self.0.upcast()
+    }
+
+    /// Recover a function handle from an erased handle, if it holds a function
+    /// with exactly this signature. Otherwise, returns the handle unchanged.
+    pub fn downcast(handle: AnyHandle<dyn Any>) -> Result<Self, AnyHandle<dyn Any>> {
+        handle.downcast().map(Self)
+    }
+}
+
+impl<Args, R> Clone for FnHandle<Args, R> {
+    /// Make a new handle to the same function.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_is_visible_to_clones() {
+        let greet = FnHandle::new(|name: &'static str| format!("hello {name}"));
+        let other = greet.clone();
+        greet.replace(|name| format!("bye {name}"));
+        assert_eq!(other.call("world"), "bye world");
+
+        let erased = other.into_any();
+        let erased = FnHandle::<u8, u8>::downcast(erased).err().unwrap();
+        assert!(FnHandle::<&'static str, String>::downcast(erased).is_ok());
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{Any, AnyHandle, Erased, Shared, Slot};
+use std::any::TypeId;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A shared pointer to an immutable value of any [Any] type, allowing for
+/// downcasting.
+///
+/// Unlike [AnyHandle], there is no lock: the value is read directly through
+/// [get](Self::get) or [Deref], which suits configuration and other data that
+/// never changes after construction. A handle can be made with
+/// [freeze](AnyHandle::freeze) and turned back into a writable one with
+/// [thaw](Self::thaw) once it is the only reference to its value.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, FrozenAnyHandle};
+///
+/// struct Config { workers: usize }
+///
+/// let config: AnyHandle<Config> = AnyHandle::new(Box::new(Config { workers: 1 })).downcast().ok().unwrap();
+/// config.write().workers = 4;
+///
+/// let frozen: FrozenAnyHandle<Config> = config.freeze().ok().unwrap();
+/// let shared = frozen.clone();
+/// assert_eq!(shared.workers, 4);
+///
+/// drop(shared);
+/// let config = frozen.thaw().ok().unwrap();
+/// config.write().workers = 8;
+/// ```
+pub struct FrozenAnyHandle<T: ?Sized>(Arc<Slot>, PhantomData<fn(T) -> T>);
+
+// SAFETY: as with AnyHandle, the slot only holds `Send + Sync` values.
+unsafe impl<T: ?Sized> Send for FrozenAnyHandle<T> {}
+unsafe impl<T: ?Sized> Sync for FrozenAnyHandle<T> {}
+
+impl FrozenAnyHandle<dyn Any> {
+    /// Initialize a FrozenAnyHandle from a [Box]<dyn [Any] + [Send] + [Sync]>.
+    pub fn new(inner: Box<dyn Any + Send + Sync>) -> Self {
+        let Ok(frozen) = AnyHandle::new(inner).freeze() else {
+            unreachable!("a new handle is unique")
+        };
+        frozen
+    }
+
+    /// Downcast this handle from `dyn Any` to a specific type.
+    /// If the stored data can be downcast to type Y, succeeds and
+    /// returns Ok(the cast handle).
+    /// If the data cannot be downcast, errors and returns Error(self).
+    ///
+    /// The type recorded when the handle was made is compared, so a
+    /// [lazy](AnyHandle::new_lazy) value is not produced by downcasting.
+    pub fn downcast<Y: 'static>(self) -> Result<FrozenAnyHandle<Y>, Self> {
+        if self.0.type_id == TypeId::of::<Y>() {
+            Ok(FrozenAnyHandle(self.0, PhantomData))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: ?Sized> FrozenAnyHandle<T> {
+    /// Turn this back into a writable [AnyHandle], if it is the only
+    /// reference to its value. Otherwise, returns Err(self).
+    ///
+    /// The handle resumes at the version it was frozen at.
+    pub fn thaw(self) -> Result<AnyHandle<T>, Self> {
+        match Arc::try_unwrap(self.0) {
+            Ok(slot) => Ok(AnyHandle(Shared::new(slot), PhantomData)),
+            Err(shared) => Err(Self(shared, PhantomData)),
+        }
+    }
+
+    /// Get a count of the number of living references to this object.
+    #[inline(always)]
+    pub fn reference_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+}
+
+impl<T: 'static> FrozenAnyHandle<T> {
+    /// Get a reference to the value.
+    #[inline(always)]
+    pub fn get(&self) -> &T {
+        unsafe { &*(self.0.value.get() as *const Erased as *const T) }
+    }
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Make this handle's object immutable, so it can be read without locking.
+    ///
+    /// This only succeeds if this is the only handle to the object, and it
+    /// has not been [revoked](AnyHandle::revoke) or
+    /// [poisoned](AnyHandle::is_poisoned). Otherwise, returns Err(self).
+    pub fn freeze(self) -> Result<FrozenAnyHandle<T>, Self> {
+        if self.is_poisoned() || self.is_revoked() {
+            return Err(self);
+        }
+        match Arc::try_unwrap(self.0) {
+            Ok(shared) => Ok(FrozenAnyHandle(Arc::new(shared.lock.into_inner().unwrap()), PhantomData)),
+            Err(shared) => Err(Self(shared, PhantomData)),
+        }
+    }
+}
+
+impl<T: ?Sized> Clone for FrozenAnyHandle<T> {
+    /// Make a new copy of this handle.
+    /// This will not copy the object within, and will increase the
+    /// reference count.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: 'static> Deref for FrozenAnyHandle<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freezing_requires_a_unique_handle() {
+        let handle: AnyHandle<u32> = AnyHandle::new(Box::new(7u32)).downcast().ok().unwrap();
+        let other = handle.clone();
+        let handle = handle.freeze().err().unwrap();
+        drop(other);
+
+        let frozen = handle.freeze().ok().unwrap();
+        let shared = frozen.clone();
+        assert_eq!(*shared.get(), 7);
+        let frozen = frozen.thaw().err().unwrap();
+        drop(shared);
+        assert_eq!(*frozen.thaw().ok().unwrap().read(), 7);
+    }
+
+    #[test]
+    fn downcasting() {
+        let frozen = FrozenAnyHandle::new(Box::new("config"));
+        let frozen = frozen.downcast::<u8>().err().unwrap();
+        assert_eq!(*frozen.downcast::<&str>().ok().unwrap().get(), "config");
+    }
+}
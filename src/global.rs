@@ -0,0 +1,69 @@
+//! Process-wide typed singletons.
+//!
+//! Each type has at most one global instance, created on first use:
+//!
+//! ```
+//! use any_handle::global;
+//!
+//! struct Metrics { requests: u64 }
+//!
+//! global::get_or_init(|| Metrics { requests: 0 }).write().requests += 1;
+//! global::get_or_init(|| Metrics { requests: 100 }).write().requests += 1;
+//!
+//! assert_eq!(global::get::<Metrics>().unwrap().read().requests, 2);
+//! ```
+
+use crate::{Any, AnyHandle, BuildTypeIdHasher};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+type Cell = Arc<OnceLock<AnyHandle<dyn Any>>>;
+
+static SINGLETONS: Mutex<HashMap<TypeId, Cell, BuildTypeIdHasher>> = Mutex::new(HashMap::with_hasher(BuildTypeIdHasher::new()));
+
+fn cell<T: 'static>() -> Cell {
+    SINGLETONS.lock().unwrap().entry(TypeId::of::<T>()).or_default().clone()
+}
+
+/// Get a handle to the global instance of `T`, creating it with `init` if
+/// it does not exist yet.
+///
+/// `init` runs at most once per type, even if several threads race to
+/// initialize the same type; the losers wait for the winner and share its
+/// value. Initializers may themselves fetch the singletons of other types,
+/// but must not recursively initialize their own type.
+pub fn get_or_init<T: Any + Send + Sync>(init: impl FnOnce() -> T) -> AnyHandle<T> {
+    cell::<T>()
+        .get_or_init(|| AnyHandle::from_value(init()).upcast())
+        .clone()
+        .cast_unchecked()
+}
+
+/// Get a handle to the global instance of `T`, if it has been initialized.
+pub fn get<T: 'static>() -> Option<AnyHandle<T>> {
+    let cell = SINGLETONS.lock().unwrap().get(&TypeId::of::<T>())?.clone();
+    cell.get().map(|handle| handle.clone().cast_unchecked())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Outer { inner: u32 }
+    struct Inner { value: u32 }
+
+    #[test]
+    fn initializers_run_once_and_may_nest() {
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let outer = get_or_init(|| Outer { inner: get_or_init(|| Inner { value: 5 }).read().value });
+                    assert_eq!(outer.read().inner, 5);
+                });
+            }
+        });
+        assert_eq!(get::<Outer>().unwrap().reference_count(), 2);
+        assert!(get::<u128>().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{Any, AnyHandle, BuildTypeIdHasher};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+trait Bucket: Send + Sync {
+    fn len(&self) -> usize;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> Bucket for Vec<AnyHandle<T>> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A collection of handles grouped by the type of their stored value.
+///
+/// Handles of each type are kept together in their own typed run, so
+/// iterating over one type walks a contiguous slice of handles and needs a
+/// single type check for the whole run, rather than one per element as with
+/// a `Vec<AnyHandle<dyn Any>>`. Insertion order is kept within each type.
+///
+/// Types are hashed with `S`, which defaults to the pass-through
+/// [BuildTypeIdHasher]; any other [BuildHasher] may be supplied through
+/// [with_hasher](Self::with_hasher).
+///
+/// # Example
+/// ```
+/// use any_handle::TypeGroupedVec;
+///
+/// struct Particle { age: u32 }
+/// struct Light { lumens: u32 }
+///
+/// let mut frame = TypeGroupedVec::new();
+/// frame.push_value(Particle { age: 1 });
+/// frame.push_value(Light { lumens: 800 });
+/// frame.push_value(Particle { age: 4 });
+///
+/// for particle in frame.of_mut::<Particle>() {
+///     particle.write().age += 1;
+/// }
+/// assert_eq!(frame.of::<Particle>().iter().map(|p| p.read().age).sum::<u32>(), 7);
+/// assert_eq!(frame.len(), 3);
+/// ```
+#[derive(Default)]
+pub struct TypeGroupedVec<S = BuildTypeIdHasher> {
+    runs: HashMap<TypeId, Box<dyn Bucket>, S>,
+}
+
+impl TypeGroupedVec {
+    /// Create an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: BuildHasher> TypeGroupedVec<S> {
+    /// Create an empty collection that hashes types with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        Self { runs: HashMap::with_hasher(hasher) }
+    }
+
+    fn run_mut<T: 'static>(&mut self) -> &mut Vec<AnyHandle<T>> {
+        self.runs
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Vec::<AnyHandle<T>>::new()))
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// Append a handle to the run for its type.
+    pub fn push<T: 'static>(&mut self, handle: AnyHandle<T>) {
+        self.run_mut().push(handle);
+    }
+
+    /// Wrap `value` in a new handle and append it, returning a handle to it.
+    pub fn push_value<T: Any + Send + Sync>(&mut self, value: T) -> AnyHandle<T> {
+        let handle = AnyHandle::from_value(value);
+        self.push(handle.clone());
+        handle
+    }
+
+    /// Get the handles to values of type `T`, in insertion order.
+    pub fn of<T: 'static>(&self) -> &[AnyHandle<T>] {
+        match self.runs.get(&TypeId::of::<T>()) {
+            Some(run) => run.as_any().downcast_ref::<Vec<AnyHandle<T>>>().unwrap(),
+            None => &[],
+        }
+    }
+
+    /// Get the handles to values of type `T` mutably, in insertion order.
+    pub fn of_mut<T: 'static>(&mut self) -> &mut [AnyHandle<T>] {
+        match self.runs.get_mut(&TypeId::of::<T>()) {
+            Some(run) => run.as_any_mut().downcast_mut::<Vec<AnyHandle<T>>>().unwrap(),
+            None => &mut [],
+        }
+    }
+
+    /// Remove the handles to values of type `T` for which `keep` returns `false`.
+    pub fn retain_of<T: 'static>(&mut self, mut keep: impl FnMut(&T) -> bool) {
+        if let Some(run) = self.runs.get_mut(&TypeId::of::<T>()) {
+            let run = run.as_any_mut().downcast_mut::<Vec<AnyHandle<T>>>().unwrap();
+            run.retain(|handle| keep(&handle.read()));
+        }
+    }
+
+    /// Remove and return every handle to a value of type `T`.
+    pub fn take_of<T: 'static>(&mut self) -> Vec<AnyHandle<T>> {
+        match self.runs.remove(&TypeId::of::<T>()) {
+            Some(mut run) => std::mem::take(run.as_any_mut().downcast_mut().unwrap()),
+            None => Vec::new(),
+        }
+    }
+
+    /// Count the handles of every type.
+    pub fn len(&self) -> usize {
+        self.runs.values().map(|run| run.len()).sum()
+    }
+
+    /// Check whether the collection holds no handles.
+    pub fn is_empty(&self) -> bool {
+        self.runs.values().all(|run| run.len() == 0)
+    }
+
+    /// Remove every handle.
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_are_kept_per_type() {
+        let mut grouped = TypeGroupedVec::new();
+        assert!(grouped.of::<u8>().is_empty());
+
+        grouped.push_value(1u8);
+        grouped.push_value("one");
+        grouped.push(AnyHandle::new(Box::new(2u8)).downcast::<u8>().ok().unwrap());
+
+        assert_eq!(grouped.of::<u8>().iter().map(|n| *n.read()).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(grouped.len(), 3);
+
+        grouped.retain_of::<u8>(|n| *n == 2);
+        assert_eq!(grouped.of::<u8>().len(), 1);
+        assert_eq!(grouped.take_of::<&str>().len(), 1);
+        assert_eq!(grouped.len(), 1);
+
+        let mut hashed = TypeGroupedVec::with_hasher(std::collections::hash_map::RandomState::new());
+        hashed.push_value(3u8);
+        assert_eq!(*hashed.of::<u8>()[0].read(), 3);
+    }
+}
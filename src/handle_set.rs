@@ -0,0 +1,162 @@
+use crate::{Any, AnyHandle};
+use std::collections::HashMap;
+
+/// A set of handles with identity semantics: two handles are the same member
+/// if they are clones of one another, regardless of the values they hold.
+///
+/// Members are kept alive by the set. Iteration order is unspecified.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, HandleSet};
+///
+/// let a = AnyHandle::new(Box::new(1u32));
+/// let b = AnyHandle::new(Box::new(1u32));
+///
+/// let selection: HandleSet = [a.clone(), b.clone()].into_iter().collect();
+/// let group: HandleSet = [b.clone()].into_iter().collect();
+///
+/// assert_eq!(selection.intersection(&group).len(), 1);
+/// assert!(selection.difference(&group).contains(&a));
+/// ```
+pub struct HandleSet<T: ?Sized = dyn Any> {
+    members: HashMap<usize, AnyHandle<T>>,
+}
+
+fn identity<T: ?Sized>(handle: &AnyHandle<T>) -> usize {
+    handle.as_ptr() as usize
+}
+
+impl<T: ?Sized> HandleSet<T> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self { members: HashMap::new() }
+    }
+
+    /// Add `handle` to the set. Returns `false` if it was already a member.
+    pub fn insert(&mut self, handle: AnyHandle<T>) -> bool {
+        let key = identity(&handle);
+        if self.members.contains_key(&key) {
+            return false;
+        }
+        self.members.insert(key, handle);
+        true
+    }
+
+    /// Remove `handle` from the set, returning the member that was removed.
+    pub fn remove(&mut self, handle: &AnyHandle<T>) -> Option<AnyHandle<T>> {
+        self.members.remove(&identity(handle))
+    }
+
+    /// Check whether `handle` is a member of the set.
+    pub fn contains(&self, handle: &AnyHandle<T>) -> bool {
+        self.members.contains_key(&identity(handle))
+    }
+
+    /// Count the members of the set.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Check whether the set has no members.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Iterate over the members of the set.
+    pub fn iter(&self) -> impl Iterator<Item = &AnyHandle<T>> {
+        self.members.values()
+    }
+
+    /// Remove every member.
+    pub fn clear(&mut self) {
+        self.members.clear();
+    }
+
+    /// Make a set of the handles that are members of either set.
+    pub fn union(&self, other: &HandleSet<T>) -> HandleSet<T> {
+        let mut union = self.clone();
+        union.extend(other.iter().cloned());
+        union
+    }
+
+    /// Make a set of the handles that are members of both sets.
+    pub fn intersection(&self, other: &HandleSet<T>) -> HandleSet<T> {
+        self.iter().filter(|handle| other.contains(handle)).cloned().collect()
+    }
+
+    /// Make a set of the handles that are members of this set but not `other`.
+    pub fn difference(&self, other: &HandleSet<T>) -> HandleSet<T> {
+        self.iter().filter(|handle| !other.contains(handle)).cloned().collect()
+    }
+
+    /// Check whether every member of this set is also a member of `other`.
+    pub fn is_subset(&self, other: &HandleSet<T>) -> bool {
+        self.iter().all(|handle| other.contains(handle))
+    }
+}
+
+impl<T: ?Sized> Default for HandleSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> Clone for HandleSet<T> {
+    /// Make a new set sharing the same members.
+    fn clone(&self) -> Self {
+        Self { members: self.members.clone() }
+    }
+}
+
+impl<T: ?Sized> Extend<AnyHandle<T>> for HandleSet<T> {
+    fn extend<I: IntoIterator<Item = AnyHandle<T>>>(&mut self, iter: I) {
+        for handle in iter {
+            self.insert(handle);
+        }
+    }
+}
+
+impl<T: ?Sized> FromIterator<AnyHandle<T>> for HandleSet<T> {
+    fn from_iter<I: IntoIterator<Item = AnyHandle<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: ?Sized> IntoIterator for HandleSet<T> {
+    type Item = AnyHandle<T>;
+    type IntoIter = std::collections::hash_map::IntoValues<usize, AnyHandle<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.into_values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let handles: Vec<_> = (0..4u8).map(|i| AnyHandle::new(Box::new(i))).collect();
+        let left: HandleSet = handles[..3].iter().cloned().collect();
+        let right: HandleSet = handles[1..].iter().cloned().collect();
+
+        assert_eq!(left.union(&right).len(), 4);
+        assert_eq!(left.intersection(&right).len(), 2);
+        let difference = left.difference(&right);
+        assert_eq!(difference.len(), 1);
+        assert!(difference.contains(&handles[0]));
+        assert!(difference.is_subset(&left));
+        assert!(!left.is_subset(&right));
+
+        let mut set = left;
+        assert!(!set.insert(handles[0].clone()));
+        assert!(set.remove(&handles[0]).is_some());
+        assert!(!set.contains(&handles[0]));
+    }
+}
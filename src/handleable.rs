@@ -0,0 +1,35 @@
+use crate::Any;
+
+/// A type which registers everything erased handles need to know about it
+/// in one call: the trait objects it can be [queried](crate::AnyHandle::query)
+/// as, and optionally its [clone](crate::register_clone) and
+/// [provider](crate::register_provider) support.
+///
+/// With the `derive` feature, `#[derive(Handleable)]` implements this from
+/// attributes on the type. See the derive macro for the attributes it accepts.
+///
+/// ```
+/// use any_handle::{register_trait, AnyHandle, Any, Handleable};
+///
+/// trait Drawable { fn draw(&self) -> String; }
+///
+/// struct Circle;
+/// impl Drawable for Circle {
+///     fn draw(&self) -> String { "circle".into() }
+/// }
+///
+/// impl Handleable for Circle {
+///     fn register() {
+///         register_trait!(Circle: dyn Drawable);
+///     }
+/// }
+///
+/// Circle::register();
+/// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(Circle).upcast();
+/// assert_eq!(handle.query::<dyn Drawable>().unwrap().read().draw(), "circle");
+/// ```
+pub trait Handleable: Any + Send + Sync {
+    /// Register this type with the crate's registries. Registering more than
+    /// once is harmless, so this can be called wherever the type is set up.
+    fn register();
+}
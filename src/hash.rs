@@ -0,0 +1,52 @@
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// A [Hasher] for [TypeId](std::any::TypeId) keys that passes the id through
+/// unchanged.
+///
+/// Type ids are already well-distributed, so running them through SipHash is
+/// wasted work. Any other input is folded with a cheap multiplicative mix,
+/// so the hasher stays correct (if not optimal) for arbitrary keys.
+#[derive(Clone, Copy, Default)]
+pub struct TypeIdHasher(u64);
+
+impl Hasher for TypeIdHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_ne_bytes(word));
+        }
+    }
+
+    #[inline(always)]
+    fn write_u64(&mut self, n: u64) {
+        self.0 = if self.0 == 0 {
+            n
+        } else {
+            (self.0.rotate_left(5) ^ n).wrapping_mul(0x517c_c1b7_2722_0a95)
+        };
+    }
+
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The default [BuildHasher](std::hash::BuildHasher) for the
+/// [TypeId](std::any::TypeId)-keyed containers in this crate.
+pub type BuildTypeIdHasher = BuildHasherDefault<TypeIdHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::any::TypeId;
+    use std::hash::BuildHasher;
+
+    #[test]
+    fn distinct_type_ids_hash_differently() {
+        let build = BuildTypeIdHasher::default();
+        assert_eq!(build.hash_one(TypeId::of::<u8>()), build.hash_one(TypeId::of::<u8>()));
+        assert_ne!(build.hash_one(TypeId::of::<u8>()), build.hash_one(TypeId::of::<u16>()));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::AnyHandle;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+
+/// Wraps a handle so that it is hashed and compared by the identity of its
+/// object, rather than by value, for use as a key in maps and sets.
+///
+/// Two wrapped handles are equal exactly when they are clones of one another.
+/// The ordering is by address, which is arbitrary but stable while both
+/// objects are alive.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, ByIdentity};
+/// use std::collections::HashMap;
+///
+/// let a = AnyHandle::from_value(1u32);
+/// let b = AnyHandle::from_value(1u32);
+///
+/// let mut visits = HashMap::new();
+/// *visits.entry(ByIdentity(a.clone())).or_insert(0) += 1;
+/// *visits.entry(ByIdentity(a)).or_insert(0) += 1;
+/// *visits.entry(ByIdentity(b)).or_insert(0) += 1;
+/// assert_eq!(visits.len(), 2);
+/// ```
+pub struct ByIdentity<T: ?Sized>(pub AnyHandle<T>);
+
+impl<T: ?Sized> ByIdentity<T> {
+    fn address(&self) -> usize {
+        self.0.as_ptr() as usize
+    }
+}
+
+impl<T: ?Sized> Clone for ByIdentity<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> Deref for ByIdentity<T> {
+    type Target = AnyHandle<T>;
+
+    #[inline(always)]
+    fn deref(&self) -> &AnyHandle<T> {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> From<AnyHandle<T>> for ByIdentity<T> {
+    fn from(handle: AnyHandle<T>) -> Self {
+        Self(handle)
+    }
+}
+
+impl<T: ?Sized> PartialEq for ByIdentity<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+}
+
+impl<T: ?Sized> Eq for ByIdentity<T> {}
+
+impl<T: ?Sized> Hash for ByIdentity<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.address().hash(state);
+    }
+}
+
+impl<T: ?Sized> PartialOrd for ByIdentity<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: ?Sized> Ord for ByIdentity<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.address().cmp(&other.address())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    // The key is ordered by address, which interior mutation cannot change.
+    #[allow(clippy::mutable_key_type)]
+    fn identity_not_value() {
+        let a = AnyHandle::from_value(0u8);
+        let b = AnyHandle::from_value(0u8);
+        assert!(a.ptr_eq(&a.clone().upcast()));
+        assert!(!a.ptr_eq(&b));
+
+        let set: BTreeSet<_> = [a.clone(), b, a].into_iter().map(ByIdentity).collect();
+        assert_eq!(set.len(), 2);
+    }
+}
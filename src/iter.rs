@@ -0,0 +1,103 @@
+use crate::{Any, AnyHandle, AnyHandleReadGuard};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+
+/// Adapters for iterators over erased handles, implemented for every iterator.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, Any, HandleIteratorExt};
+///
+/// struct Enemy { health: u32 }
+///
+/// let scene: Vec<AnyHandle<dyn Any>> = vec![
+///     AnyHandle::new(Box::new(Enemy { health: 3 })),
+///     AnyHandle::new(Box::new("a label")),
+///     AnyHandle::new(Box::new(Enemy { health: 7 })),
+/// ];
+///
+/// let total: u32 = scene.iter().filter_read_as::<Enemy>().map(|enemy| enemy.health).sum();
+/// assert_eq!(total, 10);
+///
+/// let enemies: Vec<AnyHandle<Enemy>> = scene.into_iter().filter_downcast().collect();
+/// assert_eq!(enemies.len(), 2);
+/// ```
+pub trait HandleIteratorExt: Iterator + Sized {
+    /// Downcast each handle to `T`, skipping those that hold another type.
+    fn filter_downcast<T: 'static>(self) -> FilterDowncast<Self, T>
+    where
+        Self: Iterator<Item = AnyHandle<dyn Any>>,
+    {
+        FilterDowncast { inner: self, marker: PhantomData }
+    }
+
+    /// Read-lock each borrowed handle that holds a `T`, yielding typed read
+    /// guards and skipping handles that hold another type or were revoked.
+    ///
+    /// Each guard is acquired only when the iterator reaches its handle.
+    fn filter_read_as<'a, T: 'static>(self) -> FilterReadAs<Self, T>
+    where
+        Self: Iterator<Item = &'a AnyHandle<dyn Any>>,
+    {
+        FilterReadAs { inner: self, marker: PhantomData }
+    }
+}
+
+impl<I: Iterator> HandleIteratorExt for I {}
+
+/// The iterator returned by [HandleIteratorExt::filter_downcast].
+pub struct FilterDowncast<I, T> {
+    inner: I,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<I: Iterator<Item = AnyHandle<dyn Any>>, T: 'static> Iterator for FilterDowncast<I, T> {
+    type Item = AnyHandle<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.by_ref().find_map(|handle| handle.downcast().ok())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl<I: FusedIterator<Item = AnyHandle<dyn Any>>, T: 'static> FusedIterator for FilterDowncast<I, T> {}
+
+/// The iterator returned by [HandleIteratorExt::filter_read_as].
+pub struct FilterReadAs<I, T> {
+    inner: I,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, I: Iterator<Item = &'a AnyHandle<dyn Any>>, T: 'static> Iterator for FilterReadAs<I, T> {
+    type Item = AnyHandleReadGuard<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.by_ref().find_map(|handle| handle.read_checked().ok()?.downcast().ok())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl<'a, I: FusedIterator<Item = &'a AnyHandle<dyn Any>>, T: 'static> FusedIterator for FilterReadAs<I, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_other_and_revoked_handles() {
+        let handles: Vec<AnyHandle<dyn Any>> = (0..4u8)
+            .map(|i| if i % 2 == 0 { AnyHandle::new(Box::new(i)) } else { AnyHandle::new(Box::new(i as u16)) })
+            .collect();
+        handles[2].revoke();
+
+        let values: Vec<u8> = handles.iter().filter_read_as::<u8>().map(|guard| *guard).collect();
+        assert_eq!(values, [0]);
+        assert_eq!(handles.into_iter().filter_downcast::<u16>().count(), 2);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Any, AnyHandle, Contents};
+use std::any::TypeId;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+impl<T: Any + Send + Sync> AnyHandle<T> {
+    /// Initialize a handle whose object is produced by `init` the first time
+    /// it is accessed, for resources which are expensive and may never be used.
+    ///
+    /// The initializer runs exactly once, even if several threads access the
+    /// object at the same time; the others wait for it to finish. Type queries
+    /// such as [downcast](AnyHandle::downcast) on an erased copy of the handle
+    /// don't run it, so a lazy handle can sit in a registry or map unbuilt.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let table = AnyHandle::new_lazy(|| (0..4u32).map(|n| n * n).collect::<Vec<_>>());
+    /// assert!(!table.is_initialized());
+    ///
+    /// assert_eq!(table.read()[3], 9);
+    /// assert!(table.is_initialized());
+    /// ```
+    ///
+    /// # Panics
+    /// If `init` panics, that access and every later one panics too. The
+    /// initializer must not access its own handle, which deadlocks.
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn new_lazy(init: impl FnOnce() -> T + Send + 'static) -> Self {
+        let init = Box::new(move || Box::new(init()) as Box<_>);
+        AnyHandle::from_contents(Contents::Lazy(OnceLock::new(), Mutex::new(Some(init))), TypeId::of::<T>(), Some(std::any::type_name::<T>()))
+            .cast_unchecked()
+    }
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Check whether the object has been produced, which is always the case
+    /// unless this handle was made with [new_lazy](AnyHandle::new_lazy) and
+    /// has not yet been accessed.
+    pub fn is_initialized(&self) -> bool {
+        match &self.0.read().unwrap_or_else(PoisonError::into_inner).value {
+            Contents::Lazy(value, _) => value.get().is_some(),
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn initializer_runs_once() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counter = runs.clone();
+        let handle = AnyHandle::new_lazy(move || counter.fetch_add(1, Ordering::SeqCst) + 10);
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = handle.clone();
+                std::thread::spawn(move || *handle.read())
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), 10);
+        }
+        *handle.write() += 1;
+        assert_eq!(handle.try_unwrap().ok(), Some(11));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        let unused = AnyHandle::new_lazy(|| -> String { panic!("initialized by a type query") });
+        let erased = unused.clone().upcast();
+        assert!(erased.is::<String>() && erased.try_downcast::<u8>().is_none());
+        let mut map = crate::AnyHandleMap::new();
+        map.insert(erased.clone());
+        let registry = crate::HandleRegistry::new();
+        registry.insert("unused", erased);
+        assert!(registry.get::<String>("unused").is_some() && map.contains::<String>());
+        assert!(!unused.is_initialized());
+
+        let frozen = AnyHandle::new_lazy(|| -> String { panic!("initialized by a type query") }).upcast().freeze().ok().unwrap();
+        let frozen = frozen.downcast::<u8>().err().unwrap().downcast::<String>().ok().unwrap();
+        assert!(!frozen.thaw().ok().unwrap().is_initialized());
+    }
+}
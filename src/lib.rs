@@ -1,12 +1,255 @@
 pub use std::any::Any;
+use std::any::TypeId;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::panic::{RefUnwindSafe, UnwindSafe};
+use std::sync::mpsc::Sender;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+use watch::{SlotReadGuard, SlotWriteGuard};
+
+mod any_map;
+mod any_vec;
+mod backoff;
+mod clone;
+mod cmp;
+mod cycle;
+mod double_buffered;
+mod dyn_error;
+mod error;
+mod fmt;
+mod fn_handle;
+mod frozen;
+pub mod global;
+mod grouped;
+mod handle_set;
+mod handleable;
+mod hash;
+mod identity;
+mod iter;
+mod lazy;
+mod local;
+mod lock_many;
+mod mapped;
+pub mod memory;
+mod owned;
+mod provide;
+mod query;
+mod raw;
+mod rcu;
+mod read_only;
+mod registry;
+mod replace;
+mod send_handle;
+mod sharded_map;
+mod slice;
+#[cfg(feature = "contention-stats")]
+mod stats;
+mod store;
+mod swappable;
+mod thread_local;
+mod timeout;
+mod transaction;
+mod visit;
+mod watch;
+mod weak;
+mod weak_set;
+mod write_buffer;
+
+pub use any_map::{AnyHandleMap, AnyHandleMapEntry};
+pub use any_vec::AnyVec;
+pub use backoff::BackoffPolicy;
+pub use clone::register_clone;
+pub use cycle::{CycleCollector, Trace, Tracer};
+pub use double_buffered::DoubleBufferedHandle;
+pub use error::{AccessError, DowncastError, QuotaExceeded, VersionConflict, WriteIfVersionError};
+pub use fn_handle::FnHandle;
+pub use frozen::FrozenAnyHandle;
+pub use grouped::TypeGroupedVec;
+pub use handle_set::HandleSet;
+pub use handleable::Handleable;
+#[cfg(feature = "derive")]
+pub use any_handle_derive::Handleable;
+pub use hash::{BuildTypeIdHasher, TypeIdHasher};
+pub use identity::ByIdentity;
+pub use iter::{FilterDowncast, FilterReadAs, HandleIteratorExt};
+pub use local::{AnyHandleLocal, AnyHandleLocalReadGuard, AnyHandleLocalWriteGuard};
+pub use lock_many::{lock_many, LockMany, Lockable};
+pub use mapped::{MappedAnyHandleReadGuard, MappedAnyHandleWriteGuard, ReadGuards};
+pub use owned::{OwnedAnyHandleReadGuard, OwnedAnyHandleWriteGuard};
+pub use provide::{register_provider, Provide, Request};
+pub use query::{register_cast, TraitHandle};
+pub use rcu::{RcuAnyHandle, RcuReader};
+pub use read_only::ReadOnlyAnyHandle;
+pub use registry::{HandleRegistry, Key, OverrideGuard, RegistryKey};
+pub use send_handle::{AnySendHandle, AnySendHandleGuard};
+pub use sharded_map::ShardedAnyHandleMap;
+#[cfg(feature = "contention-stats")]
+pub use stats::ContentionStats;
+pub use store::{register_id_remap, HandleId, HandleStore, IdRemap, Quota, StoreStats, TypeStats};
+pub use swappable::SwappableAnyHandle;
+pub use thread_local::ThreadLocalStore;
+pub use transaction::ReadTransaction;
+pub use visit::Visit;
+pub use weak::WeakAnyHandle;
+pub use weak_set::WeakHandleSet;
+pub use write_buffer::WriteBuffer;
+
+/// The erased type stored behind every handle.
+type Erased = dyn Any + Send + Sync;
+
+/// Produces the value of a lazy handle, see [AnyHandle::new_lazy].
+type Initializer = Box<dyn FnOnce() -> Box<Erased> + Send>;
+
+/// The stored value of a handle.
+enum Contents {
+    /// A value owned by the handle.
+    Owned(Box<Erased>),
+    /// A value borrowed for the whole program, along with a function that
+    /// copies it into owned storage on first write, if it may be written to.
+    Static(&'static Erased, Option<fn(&Erased) -> Box<Erased>>),
+    /// A value shared with the copies of a [forked](HandleStore::fork) store,
+    /// along with a function that copies it into owned storage on first write.
+    Forked(Arc<Erased>, fn(&Erased) -> Box<Erased>),
+    /// A value produced by its initializer on first access.
+    Lazy(OnceLock<Box<Erased>>, Mutex<Option<Initializer>>),
+    /// The value was dropped by [AnyHandle::revoke].
+    Revoked,
+}
+
+/// Stands in for the value of a revoked handle, so that type queries fail.
+struct Tombstone;
+
+static TOMBSTONE: Tombstone = Tombstone;
+
+impl Contents {
+    #[inline(always)]
+    fn get(&self) -> &Erased {
+        match self {
+            Contents::Owned(value) => &**value,
+            Contents::Static(value, _) => *value,
+            Contents::Forked(value, _) => &**value,
+            Contents::Lazy(value, init) => &**value.get_or_init(|| {
+                let init = init.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+                init.expect("the initializer of a lazy handle panicked")()
+            }),
+            Contents::Revoked => &TOMBSTONE,
+        }
+    }
+
+    #[inline(always)]
+    fn is_revoked(&self) -> bool {
+        matches!(self, Contents::Revoked)
+    }
+
+    #[inline(always)]
+    fn is_read_only(&self) -> bool {
+        matches!(self, Contents::Static(_, None))
+    }
+
+    /// Check that the value may be written to, before a write begins.
+    fn writable(&self) -> Result<(), AccessError> {
+        match self {
+            Contents::Revoked => Err(AccessError::Revoked),
+            Contents::Static(_, None) => Err(AccessError::ReadOnly),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run the initializer of a lazy value, if it has not run yet, and move
+    /// the value into owned storage.
+    fn initialize(&mut self) {
+        if let Contents::Lazy(..) = self {
+            self.get();
+            if let Contents::Lazy(value, _) = std::mem::replace(self, Contents::Revoked) {
+                *self = Contents::Owned(value.into_inner().unwrap());
+            }
+        }
+    }
+
+    /// Get mutable access to the value, promoting a borrowed value to owned storage.
+    ///
+    /// Must not be called on revoked contents.
+    fn get_mut(&mut self) -> &mut Erased {
+        self.initialize();
+        if let Contents::Static(value, promote) = self {
+            let promote = promote.expect("cannot write to a read-only handle");
+            *self = Contents::Owned(promote(*value));
+        }
+        if let Contents::Forked(value, copy) = self {
+            *self = Contents::Owned(copy(&**value));
+        }
+        assert!(!self.is_revoked(), "cannot write to a revoked handle");
+        match self {
+            Contents::Owned(value) => &mut **value,
+            Contents::Static(..) | Contents::Forked(..) | Contents::Lazy(..) | Contents::Revoked => unreachable!(),
+        }
+    }
+}
+
+/// The lock-protected contents of a handle.
+struct Slot {
+    value: Contents,
+    version: u64,
+    /// The type of the stored value, recorded at construction so that it is
+    /// known before a lazy value has been produced.
+    type_id: TypeId,
+    /// The name of the stored type, when it was known at construction.
+    type_name: Option<&'static str>,
+    /// Subscribers to be sent the new version after each write, by id. This
+    /// has its own lock so that subscribers can come and go under a read lock.
+    watchers: Mutex<Vec<(u64, Sender<u64>)>>,
+    #[cfg(feature = "track-origin")]
+    origin: &'static std::panic::Location<'static>,
+    #[cfg(feature = "contention-stats")]
+    stats: Arc<stats::Counters>,
+    /// When the current write lock was acquired, to time how long it is held.
+    #[cfg(feature = "contention-stats")]
+    write_acquired: Option<std::time::Instant>,
+}
+
+/// The allocation shared by a handle and its clones.
+struct Shared {
+    lock: RwLock<Slot>,
+    /// Copied from the slot, so type queries never take the lock.
+    type_id: TypeId,
+    /// Set once the slot's value is revoked, for the same reason.
+    revoked: AtomicBool,
+    /// Also held by the slot, but readable here without taking the lock.
+    #[cfg(feature = "contention-stats")]
+    stats: Arc<stats::Counters>,
+}
+
+impl Shared {
+    fn new(slot: Slot) -> Arc<Self> {
+        Arc::new(Shared {
+            type_id: slot.type_id,
+            revoked: AtomicBool::new(slot.value.is_revoked()),
+            #[cfg(feature = "contention-stats")]
+            stats: slot.stats.clone(),
+            lock: RwLock::new(slot),
+        })
+    }
+}
+
+impl Deref for Shared {
+    type Target = RwLock<Slot>;
+
+    #[inline(always)]
+    fn deref(&self) -> &RwLock<Slot> {
+        &self.lock
+    }
+}
 
 /// A thread-safe shared pointer to a value of any [Any] type, allowing for downcasting.
 ///
 /// Internally, this uses [RwLock], allowing for multiple concurrent readers
-/// or a single writer.
+/// or a single writer. Stored values must be [Send] and [Sync], so handles
+/// may be freely shared between threads.
+///
+/// Each handle carries a version number which is incremented whenever a write
+/// guard is acquired, allowing optimistic read-modify-write flows through
+/// [write_if_version](AnyHandle::write_if_version).
 ///
 /// # Example
 /// ```
@@ -27,7 +270,7 @@ use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 ///     // Now we can put it in some sort of generic container...
 ///
 ///     // ...and when we retrieve it later:
-///     let mut handle : AnyHandle<SomeStruct> = handle.downcast().ok()?;
+///     let handle : AnyHandle<SomeStruct> = handle.downcast().ok()?;
 ///     handle.write().do_mut_things_with();
 ///     handle.read().do_things_with();
 ///     Some(())
@@ -35,12 +278,134 @@ use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
 ///
 /// fn main() { demo().unwrap() }
 /// ```
-pub struct AnyHandle<T: ?Sized>(Arc<RwLock<Box<dyn Any>>>, PhantomData<T>);
+///
+/// # Thread safety
+/// Values are erased as `dyn Any + Send + Sync`, so `AnyHandle<T>` is always
+/// [Send] and [Sync], whatever `T` is. Values that are not thread-safe are
+/// rejected when the handle is created:
+///
+/// ```compile_fail
+/// use any_handle::AnyHandle;
+///
+/// let handle = AnyHandle::new(Box::new(std::rc::Rc::new(1)));
+/// ```
+///
+/// Values which are [Send] but not [Sync] can be stored in an
+/// [AnySendHandle] instead.
+///
+/// # Variance
+/// Since the value behind a handle can be written to, `AnyHandle<T>` is
+/// invariant in `T`, just like `&mut T`. A handle to a longer-lived type
+/// can't be coerced into a handle to a shorter-lived one:
+///
+/// ```compile_fail
+/// use any_handle::AnyHandle;
+///
+/// fn shorten<'a>(handle: AnyHandle<&'static str>) -> AnyHandle<&'a str> {
+///     handle
+/// }
+/// ```
+///
+/// # Unwind safety
+/// Handles and their guards are [UnwindSafe] and [RefUnwindSafe], so they can
+/// be used across [catch_unwind](std::panic::catch_unwind) without wrapping.
+/// A panic while a write guard is held poisons the object, and later attempts
+/// to lock it fail with [AccessError::Poisoned] (or panic, for
+/// [read](AnyHandle::read) and [write](AnyHandle::write)) rather than observe
+/// a half-finished write, until [clear_poison](AnyHandle::clear_poison) is called.
+pub struct AnyHandle<T: ?Sized>(Arc<Shared>, PhantomData<fn(T) -> T>);
+
+// SAFETY: The stored value is always `Send + Sync`, regardless of the marker
+// type `T`, which only records what the value may be viewed as.
+unsafe impl<T: ?Sized> Send for AnyHandle<T> {}
+unsafe impl<T: ?Sized> Sync for AnyHandle<T> {}
 
 impl AnyHandle<dyn Any> {
-    /// Initialize an AnyHandle from a [Box]<dyn [Any]>.
-    pub fn new(inner: Box<dyn Any>) -> Self {
-        Self(Arc::new(RwLock::new(inner)), PhantomData)
+    /// Initialize an AnyHandle from a [Box]<dyn [Any] + [Send] + [Sync]>.
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn new(inner: Box<dyn Any + Send + Sync>) -> Self {
+        let type_id = (*inner).type_id();
+        Self::from_contents(Contents::Owned(inner), type_id, None)
+    }
+
+    /// Initialize an AnyHandle that borrows a value living for the whole
+    /// program, such as a baked-in default, instead of allocating storage for it.
+    ///
+    /// The value is copied into owned storage the first time it is written to,
+    /// so it behaves exactly like a handle created with [new](Self::new).
+    /// Only the handle's own shared allocation is made up front.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// #[derive(Clone)]
+    /// struct Settings { volume: u8 }
+    ///
+    /// static DEFAULTS: Settings = Settings { volume: 80 };
+    ///
+    /// let handle: AnyHandle<dyn Any> = AnyHandle::borrowed(&DEFAULTS);
+    /// let settings: AnyHandle<Settings> = handle.downcast().ok().unwrap();
+    /// assert!(settings.is_borrowed());
+    ///
+    /// settings.write().volume = 20;
+    /// assert!(!settings.is_borrowed());
+    /// assert_eq!(DEFAULTS.volume, 80);
+    /// ```
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn borrowed<T: Any + Clone + Send + Sync>(value: &'static T) -> Self {
+        fn promote<T: Any + Clone + Send + Sync>(value: &Erased) -> Box<Erased> {
+            Box::new(value.downcast_ref::<T>().unwrap().clone())
+        }
+        Self::from_contents(Contents::Static(value, Some(promote::<T>)), TypeId::of::<T>(), Some(std::any::type_name::<T>()))
+    }
+
+    /// Initialize a read-only AnyHandle over a value living for the whole
+    /// program, such as a constant lookup table, without allocating storage for it.
+    ///
+    /// The handle supports reading and downcasting like any other, so constant
+    /// data can live in the same containers as mutable state, but attempts to
+    /// write to it fail with [AccessError::ReadOnly], without disturbing other
+    /// readers (see [is_read_only](AnyHandle::is_read_only)).
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// static PRIMES: [u32; 4] = [2, 3, 5, 7];
+    ///
+    /// let handle: AnyHandle<dyn Any> = AnyHandle::from_static(&PRIMES);
+    /// let primes: AnyHandle<[u32; 4]> = handle.downcast().ok().unwrap();
+    /// assert!(primes.is_read_only());
+    /// assert_eq!(primes.read()[2], 5);
+    /// ```
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn from_static<T: Any + Send + Sync>(value: &'static T) -> Self {
+        Self::from_contents(Contents::Static(value, None), TypeId::of::<T>(), Some(std::any::type_name::<T>()))
+    }
+
+    /// Consume this handle and take back its boxed object, if this is the
+    /// only reference to it. Otherwise, returns Err(self).
+    ///
+    /// See [try_unwrap](AnyHandle::try_unwrap) for the typed equivalent.
+    pub fn into_boxed_any(self) -> Result<Box<dyn Any + Send + Sync>, Self> {
+        self.into_owned()
+    }
+
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    fn from_contents(value: Contents, type_id: TypeId, type_name: Option<&'static str>) -> Self {
+        let slot = Slot {
+            value,
+            version: 0,
+            type_id,
+            type_name,
+            watchers: Mutex::new(Vec::new()),
+            #[cfg(feature = "track-origin")]
+            origin: std::panic::Location::caller(),
+            #[cfg(feature = "contention-stats")]
+            stats: Arc::default(),
+            #[cfg(feature = "contention-stats")]
+            write_acquired: None,
+        };
+        Self(Shared::new(slot), PhantomData)
     }
 
     /// Downcast this handle from `dyn Any` to a specific type.
@@ -48,14 +413,98 @@ impl AnyHandle<dyn Any> {
     /// returns Ok(the cast AnyHandle).
     /// If the data cannot be downcast, errors and returns Error(self).
     ///
-    /// You may also downcast using `Option<AnyHandle<T>>::from`.
+    /// Use [downcast_verbose](Self::downcast_verbose) for an error describing
+    /// the stored and requested types.
     pub fn downcast<Y: 'static>(self) -> Result<AnyHandle<Y>, Self> {
-        if self.0.read().unwrap().is::<Y>() {
+        if self.is::<Y>() {
             Ok(AnyHandle::<Y>(self.0, PhantomData))
         } else {
             Err(self)
         }
     }
+
+    /// Get a typed handle to the object if the stored value is of type `Y`,
+    /// leaving this handle untouched either way, unlike [downcast](Self::downcast).
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(3u16).upcast();
+    /// assert!(handle.try_downcast::<u8>().is_none());
+    ///
+    /// let number = handle.try_downcast::<u16>().unwrap();
+    /// assert!(number.ptr_eq(&handle));
+    /// ```
+    pub fn try_downcast<Y: 'static>(&self) -> Option<AnyHandle<Y>> {
+        self.clone().downcast().ok()
+    }
+
+    /// Check whether the stored value is of type `Y`, without consuming the
+    /// handle as [downcast](Self::downcast) does.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(String::from("name")).upcast();
+    /// assert!(handle.is::<String>());
+    /// assert_eq!(handle.type_id(), std::any::TypeId::of::<String>());
+    /// assert_eq!(handle.type_name(), Some("alloc::string::String"));
+    /// ```
+    pub fn is<Y: 'static>(&self) -> bool {
+        self.stored_type_id() == TypeId::of::<Y>()
+    }
+
+    /// Get the [TypeId] of the stored value. Once the handle has been
+    /// [revoked](AnyHandle::revoke), this matches no type.
+    pub fn type_id(&self) -> TypeId {
+        self.stored_type_id()
+    }
+
+    /// Get the name of the stored value's type, as given by
+    /// [type_name](std::any::type_name), if it was known when the handle was
+    /// created. Handles made with [new](Self::new) from an already-erased box
+    /// return [None].
+    pub fn type_name(&self) -> Option<&'static str> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).type_name
+    }
+
+    /// Downcast this handle to a specific type without checking the type of
+    /// the stored value, avoiding the lock that [downcast](Self::downcast) takes.
+    ///
+    /// # Safety
+    /// The stored value must be of type `Y`, or the handle must have been
+    /// [revoked](AnyHandle::revoke). Accessing the object through a handle of
+    /// any other type is undefined behavior.
+    #[inline(always)]
+    pub unsafe fn downcast_unchecked<Y: 'static>(self) -> AnyHandle<Y> {
+        debug_assert!(
+            self.is_revoked() || self.is::<Y>(),
+            "downcast_unchecked to the wrong type"
+        );
+        self.cast_unchecked()
+    }
+
+    /// Downcast this handle like [downcast](Self::downcast), but on failure
+    /// return a [DowncastError] describing the stored and requested types,
+    /// which still holds the handle.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(1.5f32).upcast();
+    /// let error = handle.downcast_verbose::<u32>().err().unwrap();
+    /// assert_eq!(error.to_string(), "cannot downcast a handle holding `f32` to `u32`");
+    /// assert!(error.into_handle().downcast::<f32>().is_ok());
+    /// ```
+    pub fn downcast_verbose<Y: 'static>(self) -> Result<AnyHandle<Y>, DowncastError> {
+        let (actual, actual_name) = (self.stored_type_id(), self.type_name());
+        self.downcast().map_err(|handle| DowncastError {
+            handle,
+            expected: std::any::type_name::<Y>(),
+            actual,
+            actual_name,
+        })
+    }
 }
 
 impl<T: ?Sized> AnyHandle<T> {
@@ -64,27 +513,476 @@ impl<T: ?Sized> AnyHandle<T> {
     /// Any number of read guards can exist at a given time, but
     /// not at the same time as any write guards, so this may block
     /// or result in deadlocks if used improperly.
+    ///
+    /// # Panics
+    /// Panics if the handle has been [revoked](AnyHandle::revoke) or
+    /// [poisoned](AnyHandle::is_poisoned); see
+    /// [read_checked](AnyHandle::read_checked) for a fallible version.
     #[inline(always)]
     pub fn read(&self) -> AnyHandleReadGuard<'_, T> {
-        AnyHandleReadGuard(self.0.read().unwrap(), PhantomData)
+        self.read_checked().unwrap()
+    }
+
+    /// Get a 'read guard' like [read](AnyHandle::read), or an [AccessError]
+    /// if the object cannot be accessed.
+    pub fn read_checked(&self) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        AnyHandleReadGuard::acquire(self.0.read().map_err(|_| AccessError::Poisoned)?)
     }
 
     /// Get a 'write guard' that allows for writing to the object.
     /// Only one write guard can exist at a given time for an object,
     /// and not at the same time as any read guards, so this may
     /// block or result in deadlocks if used improperly.
+    ///
+    /// The lock provides the exclusion, so only a shared reference to the
+    /// handle is needed.
+    ///
+    /// # Panics
+    /// Panics if the handle has been [revoked](AnyHandle::revoke), is
+    /// [read-only](AnyHandle::is_read_only) or is
+    /// [poisoned](AnyHandle::is_poisoned); see
+    /// [write_checked](AnyHandle::write_checked) for a fallible version.
+    /// The lock is released first, so other holders can still read.
     #[inline(always)]
-    pub fn write(&mut self) -> AnyHandleWriteGuard<'_, T> {
-        AnyHandleWriteGuard(self.0.write().unwrap(), PhantomData)
+    pub fn write(&self) -> AnyHandleWriteGuard<'_, T> {
+        self.write_checked().unwrap()
+    }
+
+    /// Get a 'write guard' like [write](AnyHandle::write), or an [AccessError]
+    /// if the object cannot be accessed.
+    pub fn write_checked(&self) -> Result<AnyHandleWriteGuard<'_, T>, AccessError> {
+        AnyHandleWriteGuard::acquire(self.0.write().map_err(|_| AccessError::Poisoned)?)
+    }
+
+    /// Call `f` with a reference to the object, releasing the read lock as
+    /// soon as it returns.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let scores = AnyHandle::from_value(vec![3, 5]);
+    /// scores.with_write(|scores| scores.push(8));
+    /// assert_eq!(scores.with_read(|scores| scores.iter().sum::<i32>()), 16);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    #[doc(alias = "snapshot_with")]
+    pub fn with_read<R>(&self, f: impl FnOnce(&T) -> R) -> R
+    where
+        T: Sized + 'static,
+    {
+        f(&self.read())
+    }
+
+    /// Get a copy of the object, holding the read lock only while it is
+    /// cloned. Use [with_read](AnyHandle::with_read) to copy out just part of it.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let names = AnyHandle::from_value(vec![String::from("ada")]);
+    /// let mut copy = names.snapshot();
+    /// copy.push(String::from("grace"));
+    /// assert_eq!((names.read().len(), copy.len()), (1, 2));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    pub fn snapshot(&self) -> T
+    where
+        T: Clone + 'static,
+    {
+        self.read().clone()
+    }
+
+    /// Call `f` with a mutable reference to the object, releasing the write
+    /// lock as soon as it returns.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [write](AnyHandle::write).
+    pub fn with_write<R>(&self, f: impl FnOnce(&mut T) -> R) -> R
+    where
+        T: Sized + 'static,
+    {
+        f(&mut self.write())
+    }
+
+    /// Get a read guard without blocking, or [AccessError::WouldBlock] if a
+    /// write guard currently exists for the object.
+    pub fn try_read(&self) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        let guard = match self.0.try_read() {
+            Ok(guard) => guard,
+            Err(TryLockError::WouldBlock) => return Err(AccessError::WouldBlock),
+            Err(TryLockError::Poisoned(_)) => return Err(AccessError::Poisoned),
+        };
+        AnyHandleReadGuard::acquire(guard)
+    }
+
+    /// Get a write guard without blocking, or [AccessError::WouldBlock] if any
+    /// other guard currently exists for the object.
+    pub fn try_write(&self) -> Result<AnyHandleWriteGuard<'_, T>, AccessError> {
+        match self.0.try_write() {
+            Ok(guard) => AnyHandleWriteGuard::acquire(guard),
+            Err(TryLockError::WouldBlock) => Err(AccessError::WouldBlock),
+            Err(TryLockError::Poisoned(_)) => Err(AccessError::Poisoned),
+        }
+    }
+
+    /// Get a write guard only if the object has not been written to since
+    /// it was observed at `expected` (see [version](Self::version)).
+    ///
+    /// On a mismatch, the lock is released and a [VersionConflict] carrying
+    /// the current version is returned, so the caller can re-read and retry.
+    ///
+    /// # Panics
+    /// Panics if the handle has been [revoked](AnyHandle::revoke), is
+    /// [read-only](AnyHandle::is_read_only) or is
+    /// [poisoned](AnyHandle::is_poisoned), after releasing the lock; see
+    /// [write_if_version_checked](AnyHandle::write_if_version_checked) for a
+    /// fallible version.
+    pub fn write_if_version(&self, expected: u64) -> Result<AnyHandleWriteGuard<'_, T>, VersionConflict> {
+        match self.write_if_version_checked(expected) {
+            Ok(guard) => Ok(guard),
+            Err(WriteIfVersionError::Conflict(conflict)) => Err(conflict),
+            Err(WriteIfVersionError::Access(error)) => panic!("{error}"),
+        }
+    }
+
+    /// Get a write guard like [write_if_version](AnyHandle::write_if_version),
+    /// or a [WriteIfVersionError] if the version does not match or the object
+    /// cannot be accessed.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, WriteIfVersionError};
+    ///
+    /// let handle = AnyHandle::from_value(0u32);
+    /// let seen = handle.version();
+    /// *handle.write_if_version_checked(seen).unwrap() += 1;
+    ///
+    /// let stale = handle.write_if_version_checked(seen);
+    /// assert!(matches!(stale, Err(WriteIfVersionError::Conflict(_))));
+    /// ```
+    pub fn write_if_version_checked(&self, expected: u64) -> Result<AnyHandleWriteGuard<'_, T>, WriteIfVersionError> {
+        let mut guard = SlotWriteGuard::new(self.0.write().map_err(|_| AccessError::Poisoned)?);
+        guard.value.writable()?;
+        if guard.version != expected {
+            return Err(VersionConflict { expected, actual: guard.version }.into());
+        }
+        guard.version += 1;
+        Ok(AnyHandleWriteGuard(guard, PhantomData))
+    }
+
+    /// Check whether this handle still borrows a `'static` value rather than
+    /// owning its storage (see [borrowed](AnyHandle::borrowed)).
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0.read().unwrap_or_else(PoisonError::into_inner).value, Contents::Static(..))
+    }
+
+    /// Invalidate the object for every clone of this handle, dropping its
+    /// value as soon as any outstanding guards are released.
+    ///
+    /// Afterwards, [read_checked](AnyHandle::read_checked) and
+    /// [write_checked](AnyHandle::write_checked) fail with
+    /// [AccessError::Revoked], and [read](AnyHandle::read) and
+    /// [write](AnyHandle::write) panic. Revoking is permanent, and works on
+    /// [poisoned](AnyHandle::is_poisoned) objects too.
+    pub fn revoke(&self) {
+        let mut guard = self.lock_write();
+        guard.value = Contents::Revoked;
+        self.0.revoked.store(true, Ordering::Release);
+    }
+
+    /// Check whether the object has been [revoked](AnyHandle::revoke).
+    pub fn is_revoked(&self) -> bool {
+        self.0.revoked.load(Ordering::Acquire)
+    }
+
+    /// Check whether the object is poisoned, which happens when a thread
+    /// panics while holding a write guard for it.
+    ///
+    /// Afterwards, fallible methods such as [read_checked](AnyHandle::read_checked)
+    /// fail with [AccessError::Poisoned], and [read](AnyHandle::read) and
+    /// [write](AnyHandle::write) panic, until [clear_poison](AnyHandle::clear_poison)
+    /// is called.
+    pub fn is_poisoned(&self) -> bool {
+        self.0.is_poisoned()
+    }
+
+    /// Recover from a [poisoned](AnyHandle::is_poisoned) object, accepting its
+    /// value as it was left by the panicking thread.
+    ///
+    /// # Example
+    /// ```
+    /// use any_handle::{AnyHandle, AccessError};
+    ///
+    /// let handle: AnyHandle<Vec<u32>> = AnyHandle::new(Box::new(vec![1u32])).downcast().ok().unwrap();
+    /// let worker = handle.clone();
+    /// let _ = std::thread::spawn(move || {
+    ///     worker.write().push(2);
+    ///     let _guard = worker.write();
+    ///     panic!("worker failed");
+    /// }).join();
+    ///
+    /// assert_eq!(handle.write_checked().err(), Some(AccessError::Poisoned));
+    /// handle.clear_poison();
+    /// assert_eq!(*handle.read(), [1, 2]);
+    /// ```
+    pub fn clear_poison(&self) {
+        self.0.clear_poison();
+    }
+
+    /// Check whether this handle is read-only (see [from_static](AnyHandle::from_static)).
+    pub fn is_read_only(&self) -> bool {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).value.is_read_only()
+    }
+
+    /// Get the current version of the object, which is incremented each
+    /// time a write guard is acquired.
+    pub fn version(&self) -> u64 {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).version
     }
 
     /// Get a count of the number of living references to this object.
+    /// This is the same as [strong_count](AnyHandle::strong_count).
     #[inline(always)]
     pub fn reference_count(&self) -> usize {
+        self.strong_count()
+    }
+
+    /// Get the number of strong handles to this object, which keep it alive.
+    /// See also [weak_count](AnyHandle::weak_count).
+    #[inline(always)]
+    pub fn strong_count(&self) -> usize {
         Arc::strong_count(&self.0)
     }
+
+    /// Get a pointer identifying this handle's object, shared by all of its
+    /// clones and stable for as long as the object is alive, for logging and
+    /// diagnostics. It must not be dereferenced.
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *const () {
+        Arc::as_ptr(&self.0).cast()
+    }
+
+    /// Check whether `other` refers to the same object as this handle,
+    /// as opposed to an equal one, like [Arc::ptr_eq].
+    #[inline(always)]
+    pub fn ptr_eq<U: ?Sized>(&self, other: &AnyHandle<U>) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Get the source location that created this object, for tracking down
+    /// where leaked handles come from. Requires the `track-origin` feature.
+    ///
+    /// Clones share the location of the original handle.
+    #[cfg(feature = "track-origin")]
+    pub fn origin(&self) -> &'static std::panic::Location<'static> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner).origin
+    }
+
+    /// Acquire the write lock, counting it as a new version of the object.
+    /// Poisoning is ignored, so this is only for writes that discard the
+    /// value, such as [revoke](AnyHandle::revoke).
+    pub(crate) fn lock_write(&self) -> SlotWriteGuard<'_> {
+        let mut guard = SlotWriteGuard::new(self.0.write().unwrap_or_else(PoisonError::into_inner));
+        guard.version += 1;
+        guard
+    }
+
+    /// Get the [TypeId] of the stored value, without taking the lock or
+    /// producing a lazy value.
+    pub(crate) fn stored_type_id(&self) -> TypeId {
+        if self.0.revoked.load(Ordering::Acquire) {
+            TypeId::of::<Tombstone>()
+        } else {
+            self.0.type_id
+        }
+    }
+
+    /// Take the object out of a unique handle, promoting borrowed values.
+    fn into_owned(self) -> Result<Box<Erased>, Self> {
+        // Checked before unwrapping, so that failing hands back this very
+        // handle, with its weak references and lock state intact.
+        if self.is_revoked() || self.0.read().unwrap_or_else(PoisonError::into_inner).value.is_read_only() {
+            return Err(self);
+        }
+        let shared = Arc::try_unwrap(self.0).map_err(|shared| AnyHandle(shared, PhantomData))?;
+        let mut slot = shared.lock.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        slot.value.initialize();
+        match slot.value {
+            Contents::Owned(value) => Ok(value),
+            Contents::Static(value, Some(promote)) => Ok(promote(value)),
+            Contents::Forked(value, copy) => Ok(copy(&*value)),
+            // Only reachable if another handle revoked the object after the
+            // check above, then was dropped.
+            Contents::Static(_, None) | Contents::Revoked => Err(AnyHandle(Shared::new(slot), PhantomData)),
+            Contents::Lazy(..) => unreachable!(),
+        }
+    }
+
+    /// Reinterpret this handle as a handle to `Y` without checking the stored type.
+    pub(crate) fn cast_unchecked<Y: ?Sized>(self) -> AnyHandle<Y> {
+        AnyHandle(self.0, PhantomData)
+    }
+}
+
+impl<T: Any + Send + Sync> AnyHandle<T> {
+    /// Initialize a typed AnyHandle directly from a value, without going
+    /// through [downcast](AnyHandle::downcast).
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let counter = AnyHandle::from_value(0u32);
+    /// *counter.write() += 1;
+    ///
+    /// let same: AnyHandle<u32> = 1u32.into();
+    /// assert_eq!(*counter.read(), *same.read());
+    /// ```
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn from_value(value: T) -> Self {
+        AnyHandle::from_contents(Contents::Owned(Box::new(value)), TypeId::of::<T>(), Some(std::any::type_name::<T>())).cast_unchecked()
+    }
+}
+
+impl<T: ?Sized + 'static> AnyHandle<T> {
+    /// Erase the type of this handle, so it can be stored alongside handles
+    /// of other types. This is free, as the object itself is unchanged.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// let typed = AnyHandle::from_value(5u8);
+    /// let erased: Vec<AnyHandle<dyn Any>> = vec![typed.clone().upcast(), AnyHandle::from_value("x").into()];
+    /// assert!(erased[0].clone().downcast::<u8>().is_ok());
+    /// ```
+    #[inline(always)]
+    pub fn upcast(self) -> AnyHandle<dyn Any> {
+        self.cast_unchecked()
+    }
+}
+
+impl<T: 'static> From<AnyHandle<T>> for AnyHandle<dyn Any> {
+    #[inline(always)]
+    fn from(handle: AnyHandle<T>) -> Self {
+        handle.upcast()
+    }
 }
 
+impl<T: Any + Send + Sync> From<T> for AnyHandle<T> {
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    fn from(value: T) -> Self {
+        Self::from_value(value)
+    }
+}
+
+impl<T: 'static> AnyHandle<T> {
+    /// Consume this handle and leak its object, returning a `'static` reference
+    /// which can be read without any reference counting or locking.
+    ///
+    /// This succeeds for read-only handles (see [AnyHandle::from_static]), and
+    /// for any other handle only when it is the sole reference to its object,
+    /// since no other holder may write to the object afterwards. Otherwise, or
+    /// if the handle has been revoked or is [poisoned](AnyHandle::is_poisoned),
+    /// returns Err(self).
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// struct Config { threads: usize }
+    ///
+    /// let handle: AnyHandle<Config> = AnyHandle::new(Box::new(Config { threads: 4 })).downcast().ok().unwrap();
+    /// let other = handle.clone();
+    /// let handle = handle.leak().err().unwrap();
+    ///
+    /// drop(other);
+    /// let config: &'static Config = handle.leak().ok().unwrap();
+    /// assert_eq!(config.threads, 4);
+    /// ```
+    pub fn leak(self) -> Result<&'static T, Self> {
+        // Checked before unwrapping, so that failing hands back this very handle.
+        if self.is_revoked() || self.is_poisoned() {
+            return Err(self);
+        }
+        if let Contents::Static(value, None) = self.0.read().unwrap_or_else(PoisonError::into_inner).value {
+            return Ok(value.downcast_ref::<T>().expect("typed handle holds a different type"));
+        }
+        let shared = Arc::try_unwrap(self.0).map_err(|shared| AnyHandle(shared, PhantomData))?;
+        let mut slot = shared.lock.into_inner().unwrap_or_else(|poisoned| poisoned.into_inner());
+        slot.value.initialize();
+        let value: &'static Erased = match slot.value {
+            Contents::Owned(value) => Box::leak(value),
+            Contents::Static(value, _) => value,
+            Contents::Forked(value, copy) => Box::leak(copy(&*value)),
+            Contents::Lazy(..) => unreachable!(),
+            // Only reachable if another handle revoked the object after the
+            // check above, then was dropped.
+            Contents::Revoked => {
+                return Err(AnyHandle(Shared::new(slot), PhantomData));
+            }
+        };
+        Ok(value.downcast_ref::<T>().expect("typed handle holds a different type"))
+    }
+
+    /// Get a mutable reference to the object without locking, if this is
+    /// the only handle to it, strong or [weak](AnyHandle::downgrade).
+    ///
+    /// Returns [None] if other handles exist, or the object is read-only,
+    /// revoked or poisoned. Like acquiring a write guard, this counts as a
+    /// new version of the object.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let mut handle = AnyHandle::from_value(1u32);
+    /// *handle.get_mut().unwrap() += 1;
+    ///
+    /// let other = handle.clone();
+    /// assert!(handle.get_mut().is_none());
+    /// drop(other);
+    /// assert_eq!(*handle.get_mut().unwrap(), 2);
+    /// ```
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        let slot = Arc::get_mut(&mut self.0)?.lock.get_mut().ok()?;
+        if slot.value.is_revoked() || slot.value.is_read_only() {
+            return None;
+        }
+        slot.version += 1;
+        Some(unsafe { &mut *(slot.value.get_mut() as *mut Erased as *mut T) })
+    }
+
+    /// Consume this handle and take back its object, if this is the only
+    /// reference to it. Otherwise, returns Err(self).
+    ///
+    /// A [borrowed](AnyHandle::borrowed) object is cloned out of its static
+    /// storage. Read-only and [revoked](AnyHandle::revoke) handles have no
+    /// object to give back, and also return Err(self).
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let handle = AnyHandle::from_value(String::from("scene"));
+    /// let other = handle.clone();
+    /// let handle = handle.try_unwrap().err().unwrap();
+    ///
+    /// drop(other);
+    /// assert_eq!(handle.try_unwrap().ok().unwrap(), "scene");
+    /// ```
+    pub fn try_unwrap(self) -> Result<T, Self> {
+        let value = self.into_owned()?;
+        Ok(*value.downcast::<T>().expect("typed handle holds a different type"))
+    }
+}
+
+/// **Discouraged:** prefer [downcast_verbose](AnyHandle::downcast_verbose),
+/// or [downcast](AnyHandle::downcast). This conversion is awkward to call and
+/// drops the handle on failure, while those give it back. It is kept only for
+/// compatibility, since `#[deprecated]` has no effect on trait impls.
+///
+/// A `TryFrom` conversion can't be offered alongside `From<T> for AnyHandle<T>`,
+/// since with `T = AnyHandle<dyn Any>` it would overlap the standard library's
+/// blanket `TryFrom` implementation.
 impl<T: Sized + 'static> From<AnyHandle<dyn Any>> for Option<AnyHandle<T>> {
     /// Downcast an AnyHandle<dyn [Any]> to an AnyHandle<T>.
     fn from(item: AnyHandle<dyn Any>) -> Option<AnyHandle<T>> {
@@ -105,11 +1003,122 @@ impl<T: ?Sized> Clone for AnyHandle<T> {
 
 /// An immutable view into an AnyHandle. Multiple ReadGuards may exist for the same object at a given time,
 /// but ReadGuards and WriteGuards cannot exist for the same object at the same time.
-pub struct AnyHandleReadGuard<'a, T: ?Sized + 'a>(RwLockReadGuard<'a, Box<dyn Any>>, PhantomData<T>);
+pub struct AnyHandleReadGuard<'a, T: ?Sized + 'a>(SlotReadGuard<'a>, PhantomData<T>);
 
 /// A mutable view into an AnyHandle. Only one WriteGuard may exist for the same object at a given time,
 /// but ReadGuards and WriteGuards cannot exist for the same object at the same time.
-pub struct AnyHandleWriteGuard<'a, T: ?Sized + 'a>(RwLockWriteGuard<'a, Box<dyn Any>>, PhantomData<T>);
+///
+/// Like `&mut T`, a WriteGuard is invariant in `T`:
+///
+/// ```compile_fail
+/// use any_handle::AnyHandleWriteGuard;
+///
+/// fn shorten<'g, 'a>(guard: AnyHandleWriteGuard<'g, &'static str>) -> AnyHandleWriteGuard<'g, &'a str> {
+///     guard
+/// }
+/// ```
+pub struct AnyHandleWriteGuard<'a, T: ?Sized + 'a>(SlotWriteGuard<'a>, PhantomData<fn(T) -> T>);
+
+impl<'a, T: ?Sized + 'a> AnyHandleReadGuard<'a, T> {
+    /// Wrap an acquired read lock, failing if the handle has been revoked.
+    pub(crate) fn acquire(guard: RwLockReadGuard<'a, Slot>) -> Result<Self, AccessError> {
+        if guard.value.is_revoked() {
+            return Err(AccessError::Revoked);
+        }
+        Ok(Self(SlotReadGuard::new(guard), PhantomData))
+    }
+
+    /// Get the version of the object observed by this guard.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.0.version
+    }
+
+    /// Reborrow the object as `&dyn Any`, for APIs that work on erased values.
+    #[inline(always)]
+    pub fn as_any(&self) -> &dyn Any {
+        self.0.value.get()
+    }
+}
+
+impl<'a, T: ?Sized + 'a> AnyHandleWriteGuard<'a, T> {
+    /// Wrap a freshly acquired write lock, counting it as a new version of the object.
+    pub(crate) fn acquire(guard: RwLockWriteGuard<'a, Slot>) -> Result<Self, AccessError> {
+        let mut guard = SlotWriteGuard::new(guard);
+        guard.value.writable()?;
+        guard.version += 1;
+        Ok(Self(guard, PhantomData))
+    }
+
+    /// Turn this into a read guard without releasing the lock, so no other
+    /// writer can modify the object in between.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let log = AnyHandle::from_value(vec!["started"]);
+    /// let mut guard = log.write();
+    /// guard.push("loaded");
+    ///
+    /// let guard = guard.downgrade();
+    /// assert_eq!(guard.len(), 2);
+    /// ```
+    pub fn downgrade(self) -> AnyHandleReadGuard<'a, T> {
+        AnyHandleReadGuard(self.0.downgrade(), PhantomData)
+    }
+
+    /// Get the version of the object produced by this write.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.0.version
+    }
+
+    /// Reborrow the object as `&dyn Any`, for APIs that work on erased values.
+    #[inline(always)]
+    pub fn as_any(&self) -> &dyn Any {
+        self.0.value.get()
+    }
+
+    /// Reborrow the object as `&mut dyn Any`, for APIs that work on erased values.
+    ///
+    /// # Panics
+    /// Panics if the handle is read-only (see [AnyHandle::from_static]).
+    #[inline(always)]
+    pub fn as_any_mut(&mut self) -> &mut dyn Any {
+        self.0.value.get_mut()
+    }
+}
+
+// A panic under a write guard poisons the lock, so no holder of a handle or
+// guard can observe a broken invariant after unwinding.
+impl<'a, T: ?Sized + 'a> UnwindSafe for AnyHandleReadGuard<'a, T> {}
+impl<'a, T: ?Sized + 'a> RefUnwindSafe for AnyHandleReadGuard<'a, T> {}
+impl<'a, T: ?Sized + 'a> UnwindSafe for AnyHandleWriteGuard<'a, T> {}
+impl<'a, T: ?Sized + 'a> RefUnwindSafe for AnyHandleWriteGuard<'a, T> {}
+
+impl<'a> AnyHandleReadGuard<'a, dyn Any> {
+    /// Downcast this guard to a typed guard without releasing the lock.
+    /// If the object is not a `T`, returns Err(self).
+    pub fn downcast<T: 'static>(self) -> Result<AnyHandleReadGuard<'a, T>, Self> {
+        if self.0.type_id == TypeId::of::<T>() {
+            Ok(AnyHandleReadGuard(self.0, PhantomData))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<'a> AnyHandleWriteGuard<'a, dyn Any> {
+    /// Downcast this guard to a typed guard without releasing the lock.
+    /// If the object is not a `T`, returns Err(self).
+    pub fn downcast<T: 'static>(self) -> Result<AnyHandleWriteGuard<'a, T>, Self> {
+        if self.0.type_id == TypeId::of::<T>() {
+            Ok(AnyHandleWriteGuard(self.0, PhantomData))
+        } else {
+            Err(self)
+        }
+    }
+}
 
 // Generate the Deref implementation for both guard types.
 macro_rules! impl_deref {
@@ -120,7 +1129,7 @@ macro_rules! impl_deref {
 
             #[inline(always)]
             fn deref(&self) -> &Self::Target {
-                unsafe { &*(self.0.deref().deref() as *const dyn Any as *const T) }
+                unsafe { &*(self.0.value.get() as *const Erased as *const T) }
             }
         }
     }
@@ -133,7 +1142,7 @@ impl_deref!(AnyHandleReadGuard);
 impl<'a, T: 'a + 'static> DerefMut for AnyHandleWriteGuard<'a, T> {
     #[inline(always)]
     fn deref_mut(&mut self) -> &mut T {
-        unsafe { &mut *(self.0.deref_mut().deref_mut() as *mut dyn Any as *mut T) }
+        unsafe { &mut *(self.0.value.get_mut() as *mut Erased as *mut T) }
     }
 }
 
@@ -151,7 +1160,7 @@ mod tests {
         }));
 
         let handle : Option<AnyHandle<SomeStruct>> = handle.into();
-        let mut handle = handle.unwrap();
+        let handle = handle.unwrap();
 
         {
             let handle_two = handle.clone();
@@ -171,4 +1180,235 @@ mod tests {
         let handle = AnyHandle::new(Box::new(SomeStruct { value: 12 }));
         Into::<Option<AnyHandle<SomeStruct>>>::into(handle).unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "track-origin")]
+    fn origin_tracking() {
+        let handle = AnyHandle::new(Box::new(0u8));
+        assert_eq!(handle.clone().origin().line(), line!() - 1);
+        assert_eq!(handle.origin().file(), file!());
+    }
+
+    #[test]
+    fn non_blocking_access() {
+        let handle: AnyHandle<SomeStruct> = AnyHandle::new(Box::new(SomeStruct { value: 1 })).downcast().ok().unwrap();
+        let other = handle.clone();
+
+        let guard = handle.read();
+        assert_eq!(other.try_read().unwrap().value, 1);
+        assert_eq!(other.try_write().err(), Some(AccessError::WouldBlock));
+        drop(guard);
+
+        let guard = other.try_write().unwrap();
+        assert_eq!(handle.try_read().err(), Some(AccessError::WouldBlock));
+        drop(guard);
+
+        handle.revoke();
+        assert_eq!(handle.try_write().err(), Some(AccessError::Revoked));
+    }
+
+    #[test]
+    fn downgrading_keeps_writers_out() {
+        let handle = AnyHandle::from_value(SomeStruct { value: 1 });
+        let other = handle.clone();
+
+        let mut guard = handle.write();
+        guard.value = 2;
+        let guard = guard.downgrade();
+        assert_eq!(guard.version(), 1);
+        assert_eq!(other.try_read().unwrap().value, 2);
+        assert_eq!(other.try_write().err(), Some(AccessError::WouldBlock));
+        drop(guard);
+        assert!(other.try_write().is_ok());
+    }
+
+    #[test]
+    fn unchecked_downcasting() {
+        let handle = AnyHandle::from_value(SomeStruct { value: 9 }).upcast();
+        let typed: AnyHandle<SomeStruct> = unsafe { handle.downcast_unchecked() };
+        assert_eq!(typed.read().value, 9);
+    }
+
+    #[test]
+    fn versioned_writes() {
+        let handle: AnyHandle<SomeStruct> = AnyHandle::new(Box::new(SomeStruct { value: 1 })).downcast().ok().unwrap();
+        let other = handle.clone();
+
+        let seen = handle.read().version();
+        other.write().value = 2;
+
+        let conflict = handle.write_if_version(seen).err().unwrap();
+        assert_eq!(conflict, VersionConflict { expected: seen, actual: seen + 1 });
+
+        let mut guard = handle.write_if_version(conflict.actual).unwrap();
+        guard.value += 1;
+        assert_eq!(guard.version(), seen + 2);
+        drop(guard);
+        assert_eq!(other.read().value, 3);
+    }
+
+    #[test]
+    fn static_handles_are_read_only() {
+        static VALUE: SomeStruct = SomeStruct { value: 1 };
+        let handle: AnyHandle<SomeStruct> = AnyHandle::from_static(&VALUE).downcast().ok().unwrap();
+        let version = handle.version();
+        assert_eq!(handle.write_checked().err(), Some(AccessError::ReadOnly));
+        assert!(std::panic::catch_unwind(|| handle.write().value = 2).is_err());
+
+        assert!(!handle.is_poisoned());
+        assert_eq!(handle.read().value, 1);
+        assert_eq!(handle.version(), version);
+    }
+
+    #[test]
+    fn revoked_handles_drop_their_value() {
+        let value = Arc::new(());
+        let handle = AnyHandle::new(Box::new(value.clone()));
+        let typed: AnyHandle<Arc<()>> = handle.clone().downcast().ok().unwrap();
+        assert_eq!(Arc::strong_count(&value), 2);
+
+        handle.revoke();
+        assert_eq!(Arc::strong_count(&value), 1);
+        assert!(typed.is_revoked());
+        assert_eq!(typed.read_checked().err(), Some(AccessError::Revoked));
+        assert_eq!(typed.write_checked().err(), Some(AccessError::Revoked));
+        assert!(handle.downcast::<Arc<()>>().is_err());
+    }
+
+    #[test]
+    fn guards_reborrow_as_any() {
+        let handle = AnyHandle::new(Box::new(SomeStruct { value: 1 }));
+        assert!(handle.read().as_any().is::<SomeStruct>());
+        handle.write().as_any_mut().downcast_mut::<SomeStruct>().unwrap().value = 2;
+
+        let handle: AnyHandle<SomeStruct> = handle.downcast().ok().unwrap();
+        assert_eq!(handle.read().as_any().downcast_ref::<SomeStruct>().unwrap().value, 2);
+    }
+
+    #[test]
+    fn guard_downcasting() {
+        let handle = AnyHandle::new(Box::new(SomeStruct { value: 1 }));
+        let guard = handle.read().downcast::<u8>().err().unwrap();
+        assert_eq!(guard.downcast::<SomeStruct>().ok().unwrap().value, 1);
+
+        handle.write().downcast::<SomeStruct>().ok().unwrap().value = 5;
+        assert_eq!(handle.read().downcast::<SomeStruct>().ok().unwrap().value, 5);
+    }
+
+    #[test]
+    fn leaking_read_only_handles() {
+        static VALUE: SomeStruct = SomeStruct { value: 3 };
+        let handle: AnyHandle<SomeStruct> = AnyHandle::from_static(&VALUE).downcast().ok().unwrap();
+        let _other = handle.clone();
+        assert!(std::ptr::eq(handle.leak().ok().unwrap(), &VALUE));
+
+        let handle: AnyHandle<SomeStruct> = AnyHandle::new(Box::new(SomeStruct { value: 4 })).downcast().ok().unwrap();
+        let weak = handle.downgrade();
+        handle.revoke();
+        let handle = handle.leak().err().unwrap();
+        assert!(weak.upgrade().unwrap().ptr_eq(&handle));
+
+        let poisoned = AnyHandle::from_value(SomeStruct { value: 5 });
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = poisoned.write();
+            panic!("poisoning the handle");
+        });
+        let poisoned = poisoned.leak().err().unwrap();
+        assert!(poisoned.is_poisoned());
+    }
+
+    #[test]
+    fn thread_safety() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AnyHandle<dyn Any>>();
+        assert_send_sync::<AnyHandle<std::cell::Cell<u8>>>();
+        assert_send_sync::<WeakAnyHandle<dyn Any>>();
+    }
+
+    #[test]
+    fn unwrapping_unique_handles() {
+        #[derive(Clone)]
+        struct Settings { volume: u8 }
+        static DEFAULTS: Settings = Settings { volume: 5 };
+
+        let borrowed = AnyHandle::borrowed(&DEFAULTS);
+        let boxed = borrowed.into_boxed_any().ok().unwrap();
+        assert_eq!(boxed.downcast_ref::<Settings>().unwrap().volume, 5);
+
+        let read_only = AnyHandle::from_static(&DEFAULTS);
+        let weak = read_only.downgrade();
+        let read_only = read_only.into_boxed_any().err().unwrap();
+        assert!(weak.upgrade().unwrap().ptr_eq(&read_only));
+
+        let revoked = AnyHandle::from_value(SomeStruct { value: 1 });
+        let weak = revoked.downgrade();
+        revoked.revoke();
+        let revoked = revoked.try_unwrap().err().unwrap();
+        assert!(weak.upgrade().unwrap().ptr_eq(&revoked));
+    }
+
+    #[test]
+    fn poisoned_handles_still_report_metadata() {
+        let handle = AnyHandle::from_value(SomeStruct { value: 1 });
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = handle.write();
+            panic!("poisoning the handle");
+        });
+        assert!(handle.is_poisoned());
+
+        assert_eq!(handle.version(), 1);
+        assert!(!handle.is_read_only() && !handle.is_borrowed() && handle.is_initialized());
+        assert_eq!(handle.write_if_version_checked(1).err(), Some(WriteIfVersionError::Access(AccessError::Poisoned)));
+        assert_eq!(handle.read_owned_checked().err(), Some(AccessError::Poisoned));
+        assert_eq!(handle.write_owned_checked().err(), Some(AccessError::Poisoned));
+        let erased = handle.clone().upcast();
+        assert_eq!(erased.type_name(), Some(std::any::type_name::<SomeStruct>()));
+        assert_eq!(ReadTransaction::new_checked(&[&erased]).err(), Some(AccessError::Poisoned));
+
+        handle.clear_poison();
+        assert!(handle.write_if_version_checked(1).is_ok());
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = handle.write();
+            panic!("poisoning the handle again");
+        });
+        handle.revoke();
+        assert!(handle.is_revoked());
+    }
+
+    #[test]
+    fn unwind_safety() {
+        fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
+        assert_unwind_safe::<AnyHandle<dyn Any>>();
+        assert_unwind_safe::<AnyHandle<SomeStruct>>();
+        assert_unwind_safe::<AnyHandleReadGuard<'_, SomeStruct>>();
+        assert_unwind_safe::<AnyHandleWriteGuard<'_, dyn Any>>();
+        assert_unwind_safe::<WeakHandleSet<SomeStruct>>();
+
+        let handle: AnyHandle<SomeStruct> = AnyHandle::new(Box::new(SomeStruct { value: 1 })).downcast().ok().unwrap();
+        let result = std::panic::catch_unwind(|| {
+            let mut guard = handle.write();
+            guard.value = 2;
+            panic!("plugin failed");
+        });
+        assert!(result.is_err());
+        assert_eq!(handle.try_read().err(), Some(AccessError::Poisoned));
+        assert_eq!(handle.read_checked().err(), Some(AccessError::Poisoned));
+        assert!(std::panic::catch_unwind(|| handle.read().value).is_err());
+
+        handle.clear_poison();
+        assert_eq!(handle.read().value, 2);
+    }
+
+    #[test]
+    fn introspection() {
+        let handle = AnyHandle::from_value(SomeStruct { value: 1 });
+        let clone = handle.clone();
+        let weak = handle.downgrade();
+        assert_eq!(handle.strong_count(), 2);
+        assert_eq!(handle.reference_count(), 2);
+        assert_eq!(clone.weak_count(), 1);
+        assert_eq!(handle.as_ptr(), clone.upcast().as_ptr());
+        assert_ne!(handle.as_ptr(), AnyHandle::from_value(SomeStruct { value: 1 }).as_ptr());
+        drop(weak);
+    }
 }
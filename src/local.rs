@@ -0,0 +1,163 @@
+use crate::{AccessError, Any};
+use std::cell::{Ref, RefCell, RefMut};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// A single-threaded counterpart to [AnyHandle](crate::AnyHandle), backed by
+/// [Rc] and [RefCell] instead of [Arc](std::sync::Arc) and
+/// [RwLock](std::sync::RwLock).
+///
+/// It offers the same `new`/`downcast`/`read`/`write` surface, without the
+/// cost of atomic reference counting or locking, and can store values that
+/// are neither [Send] nor [Sync]. Handles can't leave the thread that made them.
+///
+/// Borrows are checked at runtime: [read](Self::read) and [write](Self::write)
+/// panic instead of blocking when the object is already borrowed incompatibly.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandleLocal, Any};
+/// use std::rc::Rc;
+///
+/// let handle: AnyHandleLocal<dyn Any> = AnyHandleLocal::new(Box::new(Rc::new(5)));
+/// let handle: AnyHandleLocal<Rc<i32>> = handle.downcast().ok().unwrap();
+///
+/// *handle.write() = Rc::new(6);
+/// assert_eq!(**handle.read(), 6);
+/// ```
+pub struct AnyHandleLocal<T: ?Sized>(Rc<RefCell<Box<dyn Any>>>, PhantomData<fn(T) -> T>);
+
+impl AnyHandleLocal<dyn Any> {
+    /// Initialize an AnyHandleLocal from a [Box]<dyn [Any]>.
+    pub fn new(inner: Box<dyn Any>) -> Self {
+        Self(Rc::new(RefCell::new(inner)), PhantomData)
+    }
+
+    /// Downcast this handle from `dyn Any` to a specific type.
+    /// If the stored data can be downcast to type Y, succeeds and
+    /// returns Ok(the cast handle).
+    /// If the data cannot be downcast, errors and returns Error(self).
+    pub fn downcast<Y: 'static>(self) -> Result<AnyHandleLocal<Y>, Self> {
+        if self.0.borrow().is::<Y>() {
+            Ok(AnyHandleLocal(self.0, PhantomData))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: ?Sized> AnyHandleLocal<T> {
+    /// Get a 'read guard' that allows for reading from the object.
+    /// Any number of read guards can exist at a given time, but
+    /// not at the same time as a write guard.
+    ///
+    /// # Panics
+    /// Panics if a write guard currently exists for the object.
+    #[inline(always)]
+    pub fn read(&self) -> AnyHandleLocalReadGuard<'_, T> {
+        self.try_read().unwrap()
+    }
+
+    /// Get a 'write guard' that allows for writing to the object.
+    /// Only one write guard can exist at a given time for an object,
+    /// and not at the same time as any read guards.
+    ///
+    /// # Panics
+    /// Panics if any other guard currently exists for the object.
+    #[inline(always)]
+    pub fn write(&self) -> AnyHandleLocalWriteGuard<'_, T> {
+        self.try_write().unwrap()
+    }
+
+    /// Get a read guard, or [AccessError::WouldBlock] if a write guard
+    /// currently exists for the object.
+    pub fn try_read(&self) -> Result<AnyHandleLocalReadGuard<'_, T>, AccessError> {
+        let guard = self.0.try_borrow().map_err(|_| AccessError::WouldBlock)?;
+        Ok(AnyHandleLocalReadGuard(guard, PhantomData))
+    }
+
+    /// Get a write guard, or [AccessError::WouldBlock] if any other guard
+    /// currently exists for the object.
+    pub fn try_write(&self) -> Result<AnyHandleLocalWriteGuard<'_, T>, AccessError> {
+        let guard = self.0.try_borrow_mut().map_err(|_| AccessError::WouldBlock)?;
+        Ok(AnyHandleLocalWriteGuard(guard, PhantomData))
+    }
+
+    /// Get a count of the number of living references to this object.
+    #[inline(always)]
+    pub fn reference_count(&self) -> usize {
+        Rc::strong_count(&self.0)
+    }
+}
+
+impl<T: ?Sized> Clone for AnyHandleLocal<T> {
+    /// Make a new copy of this handle.
+    /// This will not copy the object within, and will increase the
+    /// reference count.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+/// Allows for reading from the object behind an [AnyHandleLocal].
+/// Multiple read guards can exist for the same object at a given time.
+pub struct AnyHandleLocalReadGuard<'a, T: ?Sized + 'a>(Ref<'a, Box<dyn Any>>, PhantomData<T>);
+
+/// Allows for writing to the object behind an [AnyHandleLocal].
+/// Only one write guard can exist for an object at a given time.
+pub struct AnyHandleLocalWriteGuard<'a, T: ?Sized + 'a>(RefMut<'a, Box<dyn Any>>, PhantomData<fn(T) -> T>);
+
+/// Deref a handle, immutably.
+impl<'a, T: 'a + 'static> Deref for AnyHandleLocalReadGuard<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        unsafe { &*(&**self.0 as *const dyn Any as *const T) }
+    }
+}
+
+/// Deref a handle, immutably.
+impl<'a, T: 'a + 'static> Deref for AnyHandleLocalWriteGuard<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        unsafe { &*(&**self.0 as *const dyn Any as *const T) }
+    }
+}
+
+/// Deref a handle, mutably.
+impl<'a, T: 'a + 'static> DerefMut for AnyHandleLocalWriteGuard<'a, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *(&mut **self.0 as *mut dyn Any as *mut T) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct Counter { count: Cell<u32> }
+
+    #[test]
+    fn shared_access() {
+        let handle = AnyHandleLocal::new(Box::new(Counter { count: Cell::new(0) }));
+        assert!(handle.clone().downcast::<u32>().is_err());
+
+        let counter = handle.downcast::<Counter>().ok().unwrap();
+        let other = counter.clone();
+        other.read().count.set(1);
+        assert_eq!(counter.reference_count(), 2);
+
+        let reading = other.read();
+        assert_eq!(counter.try_write().err(), Some(AccessError::WouldBlock));
+        drop(reading);
+        counter.write().count = Cell::new(2);
+        assert_eq!(counter.read().count.get(), 2);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{AnyHandle, AnyHandleReadGuard, AnyHandleWriteGuard};
+use std::sync::Arc;
+
+/// A request to lock one handle, for use with [lock_many].
+///
+/// A shared reference to a handle requests a read guard, and a mutable
+/// reference requests a write guard.
+pub trait Lockable {
+    /// The guard produced by locking this handle.
+    type Guard;
+
+    /// Get the address which orders this lock relative to others.
+    fn address(&self) -> usize;
+
+    /// Lock the handle, blocking until it is available.
+    fn lock(self) -> Self::Guard;
+}
+
+impl<'a, T: ?Sized> Lockable for &'a AnyHandle<T> {
+    type Guard = AnyHandleReadGuard<'a, T>;
+
+    fn address(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+
+    fn lock(self) -> Self::Guard {
+        self.read()
+    }
+}
+
+impl<'a, T: ?Sized> Lockable for &'a mut AnyHandle<T> {
+    type Guard = AnyHandleWriteGuard<'a, T>;
+
+    fn address(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+
+    fn lock(self) -> Self::Guard {
+        self.write()
+    }
+}
+
+/// A tuple of [Lockable] handles which can be locked together with [lock_many].
+pub trait LockMany {
+    /// The tuple of guards produced by locking every handle.
+    type Guards;
+
+    /// Lock every handle, in order of address.
+    fn lock_many(self) -> Self::Guards;
+}
+
+macro_rules! impl_lock_many {
+    ($($L:ident $index:tt),+) => {
+        impl<$($L: Lockable),+> LockMany for ($($L,)+) {
+            type Guards = ($($L::Guard,)+);
+
+            #[allow(non_snake_case)]
+            fn lock_many(self) -> Self::Guards {
+                let mut order = [$((self.$index.address(), $index)),+];
+                order.sort_unstable();
+                assert!(
+                    order.windows(2).all(|pair| pair[0].0 != pair[1].0),
+                    "lock_many was given the same object more than once"
+                );
+
+                $(let mut $L = (Some(self.$index), None);)+
+                for (_, index) in order {
+                    match index {
+                        $($index => $L.1 = $L.0.take().map(Lockable::lock),)+
+                        _ => unreachable!(),
+                    }
+                }
+                ($($L.1.unwrap(),)+)
+            }
+        }
+    };
+}
+
+impl_lock_many!(A 0, B 1);
+impl_lock_many!(A 0, B 1, C 2);
+impl_lock_many!(A 0, B 1, C 2, D 3);
+
+/// Lock several handles at once, returning a tuple of guards.
+///
+/// Locks are always acquired in order of the objects' addresses, the same
+/// order used by [ReadTransaction](crate::ReadTransaction), so callers
+/// locking overlapping sets of handles can never deadlock one another.
+/// Each handle given as `&handle` is read-locked, and each given as
+/// `&mut handle` is write-locked.
+///
+/// ```
+/// use any_handle::{AnyHandle, lock_many};
+///
+/// struct Account { balance: u32 }
+///
+/// let mut from = AnyHandle::from_value(Account { balance: 10 });
+/// let mut to = AnyHandle::from_value(Account { balance: 0 });
+///
+/// let (mut from_guard, mut to_guard) = lock_many((&mut from, &mut to));
+/// from_guard.balance -= 4;
+/// to_guard.balance += 4;
+/// ```
+///
+/// # Panics
+/// Panics if the same object appears more than once, or under the same
+/// conditions as [read](AnyHandle::read) and [write](AnyHandle::write).
+pub fn lock_many<L: LockMany>(handles: L) -> L::Guards {
+    handles.lock_many()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opposite_orders_do_not_deadlock() {
+        let a = AnyHandle::from_value(0u32);
+        let b = AnyHandle::from_value(0u32);
+
+        std::thread::scope(|scope| {
+            for flip in [false, true] {
+                let (mut a, mut b) = (a.clone(), b.clone());
+                scope.spawn(move || {
+                    for _ in 0..1000 {
+                        let (mut first, mut second) = if flip {
+                            let (b, a) = lock_many((&mut b, &mut a));
+                            (a, b)
+                        } else {
+                            lock_many((&mut a, &mut b))
+                        };
+                        *first += 1;
+                        *second += 1;
+                    }
+                });
+            }
+        });
+        assert_eq!((*a.read(), *b.read()), (2000, 2000));
+    }
+
+    #[test]
+    #[should_panic(expected = "more than once")]
+    fn duplicates_are_rejected() {
+        let mut a = AnyHandle::from_value(0u32);
+        let reader = a.clone();
+        lock_many((&mut a, &reader));
+    }
+}
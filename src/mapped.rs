@@ -0,0 +1,204 @@
+use crate::watch::{SlotReadGuard, SlotWriteGuard};
+use crate::{Any, AnyHandleReadGuard, AnyHandleWriteGuard};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// A read guard projected onto part of the objects it locks, produced by
+/// [map](AnyHandleReadGuard::map), [try_map](AnyHandleReadGuard::try_map)
+/// or [ReadGuards::map2].
+///
+/// The locks stay held until this guard is dropped.
+pub struct MappedAnyHandleReadGuard<'a, U: ?Sized> {
+    _guards: (SlotReadGuard<'a>, Option<SlotReadGuard<'a>>),
+    value: NonNull<U>,
+}
+
+/// A write guard projected onto part of the object it locks, produced by
+/// [map](AnyHandleWriteGuard::map) or [try_map](AnyHandleWriteGuard::try_map).
+///
+/// The lock stays held until this guard is dropped.
+pub struct MappedAnyHandleWriteGuard<'a, U: ?Sized> {
+    _guard: SlotWriteGuard<'a>,
+    value: NonNull<U>,
+    // Like `&mut U`, this guard must be invariant in `U`.
+    marker: PhantomData<&'a mut U>,
+}
+
+impl<'a, T: 'static> AnyHandleReadGuard<'a, T> {
+    /// Project this guard onto a part of the object, such as one of its
+    /// fields, keeping the lock held.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// struct Player { name: String, score: u32 }
+    ///
+    /// let player = AnyHandle::from_value(Player { name: "ann".into(), score: 3 });
+    /// let name = player.read().map(|player| player.name.as_str());
+    /// assert_eq!(&*name, "ann");
+    /// ```
+    pub fn map<U: ?Sized>(self, f: impl FnOnce(&T) -> &U) -> MappedAnyHandleReadGuard<'a, U> {
+        let value = NonNull::from(f(&self));
+        MappedAnyHandleReadGuard { _guards: (self.0, None), value }
+    }
+
+    /// Project this guard onto a part of the object chosen by `f`, keeping the
+    /// lock held. If `f` returns [None], the lock is not released and the
+    /// original guard is returned as Err(self).
+    pub fn try_map<U: ?Sized>(self, f: impl FnOnce(&T) -> Option<&U>) -> Result<MappedAnyHandleReadGuard<'a, U>, Self> {
+        match f(&self).map(NonNull::from) {
+            Some(value) => Ok(MappedAnyHandleReadGuard { _guards: (self.0, None), value }),
+            None => Err(self),
+        }
+    }
+}
+
+impl<'a, T: 'static> AnyHandleWriteGuard<'a, T> {
+    /// Project this guard onto a part of the object, such as one of its
+    /// fields, keeping the lock held.
+    pub fn map<U: ?Sized>(self, f: impl FnOnce(&mut T) -> &mut U) -> MappedAnyHandleWriteGuard<'a, U> {
+        match self.try_map(|value| Some(f(value))) {
+            Ok(mapped) => mapped,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Project this guard onto a part of the object chosen by `f`, keeping the
+    /// lock held. If `f` returns [None], the lock is not released and the
+    /// original guard is returned as Err(self).
+    pub fn try_map<U: ?Sized>(mut self, f: impl FnOnce(&mut T) -> Option<&mut U>) -> Result<MappedAnyHandleWriteGuard<'a, U>, Self> {
+        let target: *mut T = &mut *self;
+        // SAFETY: `target` points into the locked object, which lives as long
+        // as the lock is held; the reference is not used once `f` returns.
+        match f(unsafe { &mut *target }).map(NonNull::from) {
+            Some(value) => Ok(MappedAnyHandleWriteGuard { _guard: self.0, value, marker: PhantomData }),
+            None => Err(self),
+        }
+    }
+}
+
+impl<'a, U: ?Sized> MappedAnyHandleReadGuard<'a, U> {
+    /// Project this guard further, onto a part of the value chosen by `f`.
+    pub fn map<V: ?Sized>(self, f: impl FnOnce(&U) -> &V) -> MappedAnyHandleReadGuard<'a, V> {
+        let value = NonNull::from(f(&self));
+        MappedAnyHandleReadGuard { _guards: self._guards, value }
+    }
+}
+
+impl<'a, U: ?Sized> MappedAnyHandleWriteGuard<'a, U> {
+    /// Project this guard further, onto a part of the value chosen by `f`.
+    pub fn map<V: ?Sized>(mut self, f: impl FnOnce(&mut U) -> &mut V) -> MappedAnyHandleWriteGuard<'a, V> {
+        let value = NonNull::from(f(&mut self));
+        MappedAnyHandleWriteGuard { _guard: self._guard, value, marker: PhantomData }
+    }
+}
+
+impl<'a> AnyHandleReadGuard<'a, dyn Any> {
+    /// Project this erased guard onto a view of the object chosen by `f`.
+    pub(crate) fn map_any<U: ?Sized>(self, f: impl FnOnce(&dyn Any) -> &U) -> MappedAnyHandleReadGuard<'a, U> {
+        let value = NonNull::from(f(self.as_any()));
+        MappedAnyHandleReadGuard { _guards: (self.0, None), value }
+    }
+
+    /// Project this erased guard onto a view of the object chosen by `f`,
+    /// releasing the lock if `f` returns [None].
+    pub(crate) fn try_map_any<U: ?Sized>(self, f: impl FnOnce(&dyn Any) -> Option<&U>) -> Option<MappedAnyHandleReadGuard<'a, U>> {
+        let value = NonNull::from(f(self.as_any())?);
+        Some(MappedAnyHandleReadGuard { _guards: (self.0, None), value })
+    }
+}
+
+impl<'a> AnyHandleWriteGuard<'a, dyn Any> {
+    /// Project this erased guard onto a view of the object chosen by `f`.
+    pub(crate) fn map_any<U: ?Sized>(mut self, f: impl FnOnce(&mut dyn Any) -> &mut U) -> MappedAnyHandleWriteGuard<'a, U> {
+        let value = NonNull::from(f(self.as_any_mut()));
+        MappedAnyHandleWriteGuard { _guard: self.0, value, marker: PhantomData }
+    }
+}
+
+/// Combinators over several read guards at once.
+pub enum ReadGuards {}
+
+impl ReadGuards {
+    /// Combine two read guards into one guard over a part of either object
+    /// chosen by `f`, holding both locks until it is dropped.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, ReadGuards};
+    ///
+    /// struct Player { name: String, score: u32 }
+    ///
+    /// let a: AnyHandle<Player> = AnyHandle::new(Box::new(Player { name: "ann".into(), score: 3 })).downcast().ok().unwrap();
+    /// let b: AnyHandle<Player> = AnyHandle::new(Box::new(Player { name: "bob".into(), score: 5 })).downcast().ok().unwrap();
+    ///
+    /// let leader = ReadGuards::map2(a.read(), b.read(), |a, b| {
+    ///     if a.score >= b.score { a.name.as_str() } else { b.name.as_str() }
+    /// });
+    /// assert_eq!(&*leader, "bob");
+    /// ```
+    pub fn map2<'a, A: 'static, B: 'static, U: ?Sized>(
+        a: AnyHandleReadGuard<'a, A>,
+        b: AnyHandleReadGuard<'a, B>,
+        f: impl for<'r> FnOnce(&'r A, &'r B) -> &'r U,
+    ) -> MappedAnyHandleReadGuard<'a, U> {
+        let value = NonNull::from(f(&a, &b));
+        MappedAnyHandleReadGuard { _guards: (a.0, Some(b.0)), value }
+    }
+}
+
+impl<U: ?Sized> Deref for MappedAnyHandleReadGuard<'_, U> {
+    type Target = U;
+
+    #[inline(always)]
+    fn deref(&self) -> &U {
+        // SAFETY: `value` points into an object whose lock is held by `_guards`.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<U: ?Sized> Deref for MappedAnyHandleWriteGuard<'_, U> {
+    type Target = U;
+
+    #[inline(always)]
+    fn deref(&self) -> &U {
+        // SAFETY: `value` points into the object whose lock is held by `_guard`.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<U: ?Sized> DerefMut for MappedAnyHandleWriteGuard<'_, U> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut U {
+        // SAFETY: `value` points into the object whose lock is held exclusively by `_guard`.
+        unsafe { self.value.as_mut() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AnyHandle;
+
+    struct Inventory { items: Vec<u32> }
+
+    #[test]
+    fn try_map_keeps_the_lock_on_failure() {
+        let handle: AnyHandle<Inventory> = AnyHandle::new(Box::new(Inventory { items: vec![4] })).downcast().ok().unwrap();
+
+        let guard = handle.read().try_map(|inventory| inventory.items.get(1)).err().unwrap();
+        assert_eq!(*guard.try_map(|inventory| inventory.items.first()).ok().unwrap(), 4);
+
+        *handle.write().try_map(|inventory| inventory.items.first_mut()).ok().unwrap() += 1;
+        let missing = handle.write().try_map(|inventory| inventory.items.get_mut(1));
+        assert_eq!(missing.err().unwrap().items, [5]);
+    }
+
+    #[test]
+    fn map_projects_fields() {
+        let handle = AnyHandle::from_value(Inventory { items: vec![1, 2] });
+
+        *handle.write().map(|inventory| &mut inventory.items).map(|items| &mut items[1]) = 7;
+        let last = handle.read().map(|inventory| &inventory.items).map(|items| &items[1]);
+        assert_eq!(*last, 7);
+    }
+}
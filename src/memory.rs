@@ -0,0 +1,214 @@
+//! Integration with application memory budget management.
+//!
+//! Handles can register purge hooks, which drop cached or recomputable
+//! contents when [trim] is called with a [PressureLevel], and anything else,
+//! such as a container, can register a global hook with [on_memory_pressure].
+//!
+//! The containers in this crate are plain owned values, so they don't
+//! register hooks of their own; register hooks on the handles they hold, or a
+//! global hook which reaches the container through whatever shares it. To
+//! trim a single [HandleStore](crate::HandleStore), such as one over its
+//! budget, [HandleStore::trim](crate::HandleStore::trim) runs only the hooks
+//! of the handles it holds.
+//!
+//! The memory held by a value can be measured with
+//! [memory_usage](AnyHandle::memory_usage), counting heap memory for types
+//! registered with [register_size].
+
+use crate::watch::SlotWriteGuard;
+use crate::{Any, AnyHandle, BuildTypeIdHasher, Contents, Erased, Shared};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// How urgently memory should be released.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PressureLevel {
+    /// Release memory that is cheap to recompute.
+    Low,
+    /// Release most caches.
+    Moderate,
+    /// Release everything that can possibly be released.
+    Critical,
+}
+
+/// Identifies a registered purge hook, so that it may be removed later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PurgeHook(u64);
+
+/// A hook returns `false` once it can never fire again, so it can be pruned.
+type Hook = Arc<dyn Fn(PressureLevel) -> bool + Send + Sync>;
+
+/// Each hook is stored with the address of the object it purges, if it was
+/// registered on a handle.
+static HOOKS: Mutex<Vec<(PurgeHook, Option<usize>, Hook)>> = Mutex::new(Vec::new());
+static NEXT_HOOK: AtomicU64 = AtomicU64::new(0);
+
+fn register(object: Option<usize>, hook: Hook) -> PurgeHook {
+    let id = PurgeHook(NEXT_HOOK.fetch_add(1, Ordering::Relaxed));
+    HOOKS.lock().unwrap().push((id, object, hook));
+    id
+}
+
+/// Get the address identifying the object behind a handle, as stored with
+/// its hooks.
+pub(crate) fn object_address(shared: &Arc<Shared>) -> usize {
+    Arc::as_ptr(shared) as usize
+}
+
+/// Register `hook` to be called on every [trim].
+pub fn on_memory_pressure(hook: impl Fn(PressureLevel) + Send + Sync + 'static) -> PurgeHook {
+    register(None, Arc::new(move |level| {
+        hook(level);
+        true
+    }))
+}
+
+/// Remove a previously registered hook.
+/// Returns `false` if it had already been removed.
+pub fn remove_hook(hook: PurgeHook) -> bool {
+    let mut hooks = HOOKS.lock().unwrap();
+    let before = hooks.len();
+    hooks.retain(|(id, ..)| *id != hook);
+    hooks.len() != before
+}
+
+/// Invoke every registered purge hook with `level`.
+///
+/// Hooks run without any internal lock held, so they may themselves register
+/// or remove hooks.
+pub fn trim(level: PressureLevel) {
+    trim_where(level, |_| true);
+}
+
+/// Invoke the purge hooks for which `selected` returns `true` when given the
+/// address of the object they purge, or [None] for global hooks.
+pub(crate) fn trim_where(level: PressureLevel, selected: impl Fn(Option<usize>) -> bool) {
+    let hooks: Vec<_> = HOOKS.lock().unwrap().iter().filter(|(_, object, _)| selected(*object)).cloned().collect();
+    let expired: Vec<_> = hooks.into_iter().filter(|(.., hook)| !hook(level)).map(|(id, ..)| id).collect();
+    if !expired.is_empty() {
+        HOOKS.lock().unwrap().retain(|(id, ..)| !expired.contains(id));
+    }
+}
+
+type SizeFn = Box<dyn Fn(&Erased) -> usize + Send + Sync>;
+
+static SIZES: RwLock<HashMap<TypeId, SizeFn, BuildTypeIdHasher>> = RwLock::new(HashMap::with_hasher(BuildTypeIdHasher::new()));
+
+/// Register `size` as reporting the heap memory owned by a value of type `T`,
+/// beyond the value itself, for [memory_usage](AnyHandle::memory_usage).
+///
+/// ```
+/// use any_handle::{memory, AnyHandle};
+///
+/// let names = AnyHandle::from_value(vec![0u64; 16]);
+/// assert_eq!(names.memory_usage(), std::mem::size_of::<Vec<u64>>());
+///
+/// memory::register_size::<Vec<u64>>(|items| items.capacity() * 8);
+/// assert_eq!(names.memory_usage(), std::mem::size_of::<Vec<u64>>() + 128);
+/// ```
+pub fn register_size<T: Any>(size: fn(&T) -> usize) {
+    let size: SizeFn = Box::new(move |value| size(value.downcast_ref().expect("size invoked on the wrong type")));
+    SIZES.write().unwrap().insert(TypeId::of::<T>(), size);
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Estimate the memory held by the object, in bytes: its own size, plus
+    /// the heap memory reported for its type by [register_size], if any.
+    ///
+    /// Revoked objects, and [lazy](AnyHandle::new_lazy) ones which have not
+    /// been produced yet, use no memory.
+    ///
+    /// This takes the read lock, so it waits for any writer, and deadlocks if
+    /// the current thread holds a write guard for the object.
+    pub fn memory_usage(&self) -> usize {
+        let slot = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        let value = match &slot.value {
+            Contents::Revoked => return 0,
+            Contents::Lazy(value, _) => match value.get() {
+                Some(value) => &**value,
+                None => return 0,
+            },
+            contents => contents.get(),
+        };
+        let heap = SIZES.read().unwrap().get(&slot.type_id).map_or(0, |size| size(value));
+        std::mem::size_of_val(value) + heap
+    }
+}
+
+impl<T: 'static> AnyHandle<T> {
+    /// Register `purge` to be run against this handle's value on every [trim].
+    ///
+    /// The hook does not keep the value alive, and is removed automatically
+    /// once every handle to it has been dropped, or immediately for read-only
+    /// and revoked handles, whose values can never be purged. If the value is locked when
+    /// [trim] runs, it is skipped for that call rather than risking a deadlock.
+    pub fn on_memory_pressure(&self, purge: impl Fn(&mut T, PressureLevel) + Send + Sync + 'static) -> PurgeHook {
+        let weak = Arc::downgrade(&self.0);
+        register(Some(object_address(&self.0)), Arc::new(move |level| {
+            let Some(shared) = weak.upgrade() else {
+                return false;
+            };
+            if let Ok(mut slot) = shared.try_write().map(SlotWriteGuard::new) {
+                if slot.value.is_read_only() || slot.value.is_revoked() {
+                    return false;
+                }
+                slot.version += 1;
+                purge(slot.value.get_mut().downcast_mut::<T>().expect("typed handle holds a different type"), level);
+            }
+            true
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Cache { entries: Vec<u8> }
+
+    #[test]
+    fn trim_purges_live_handles() {
+        let handle: AnyHandle<Cache> = AnyHandle::new(Box::new(Cache { entries: vec![1, 2, 3] })).downcast().ok().unwrap();
+        let hook = handle.on_memory_pressure(|cache, level| {
+            if level >= PressureLevel::Moderate {
+                cache.entries.clear();
+            }
+        });
+
+        trim(PressureLevel::Low);
+        assert_eq!(handle.read().entries.len(), 3);
+        trim(PressureLevel::Critical);
+        assert!(handle.read().entries.is_empty());
+
+        drop(handle);
+        trim(PressureLevel::Low);
+        assert!(!remove_hook(hook));
+    }
+
+    #[test]
+    fn unproduced_values_use_no_memory() {
+        let lazy = AnyHandle::new_lazy(|| [0u8; 64]);
+        assert_eq!(lazy.memory_usage(), 0);
+        assert_eq!(lazy.read()[0], 0);
+        assert_eq!(lazy.memory_usage(), 64);
+
+        lazy.revoke();
+        assert_eq!(lazy.memory_usage(), 0);
+    }
+
+    #[test]
+    fn global_hooks_can_be_removed() {
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = calls.clone();
+        let hook = on_memory_pressure(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        trim(PressureLevel::Low);
+        assert!(remove_hook(hook));
+        trim(PressureLevel::Low);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{AccessError, AnyHandle, AnyHandleReadGuard, AnyHandleWriteGuard, Shared, Slot};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, RwLock};
+
+/// A read guard that keeps its object alive, so it is not tied to the
+/// lifetime of a handle. Produced by [read_owned](AnyHandle::read_owned).
+///
+/// Like any other guard, it must be dropped on the thread that acquired it.
+pub struct OwnedAnyHandleReadGuard<T: ?Sized + 'static> {
+    // Declared first, so the lock is released before the object is.
+    guard: AnyHandleReadGuard<'static, T>,
+    _shared: Arc<Shared>,
+}
+
+/// A write guard that keeps its object alive, so it is not tied to the
+/// lifetime of a handle. Produced by [write_owned](AnyHandle::write_owned).
+///
+/// Like any other guard, it must be dropped on the thread that acquired it.
+pub struct OwnedAnyHandleWriteGuard<T: ?Sized + 'static> {
+    // Declared first, so the lock is released before the object is.
+    guard: AnyHandleWriteGuard<'static, T>,
+    _shared: Arc<Shared>,
+}
+
+impl<T: ?Sized + 'static> AnyHandle<T> {
+    /// Extend a borrow of this handle's lock to `'static`, alongside a
+    /// reference which keeps the lock alive.
+    fn detached_lock(&self) -> (&'static RwLock<Slot>, Arc<Shared>) {
+        let shared = self.0.clone();
+        // SAFETY: the lock lives as long as `shared`, which the owned guards
+        // hold until after their borrow of it has been dropped.
+        (unsafe { &(*Arc::as_ptr(&shared)).lock }, shared)
+    }
+
+    /// Get a read guard like [read](AnyHandle::read), which holds its own
+    /// reference to the object instead of borrowing this handle.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, OwnedAnyHandleReadGuard};
+    ///
+    /// struct Frame { guards: Vec<OwnedAnyHandleReadGuard<u32>> }
+    ///
+    /// let mut frame = Frame { guards: Vec::new() };
+    /// {
+    ///     let handle = AnyHandle::from_value(7u32);
+    ///     frame.guards.push(handle.read_owned());
+    /// }
+    /// assert_eq!(*frame.guards[0], 7);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read); see
+    /// [read_owned_checked](AnyHandle::read_owned_checked) for a fallible version.
+    pub fn read_owned(&self) -> OwnedAnyHandleReadGuard<T> {
+        self.read_owned_checked().unwrap()
+    }
+
+    /// Get an owned read guard like [read_owned](AnyHandle::read_owned), or an
+    /// [AccessError] if the object cannot be accessed.
+    pub fn read_owned_checked(&self) -> Result<OwnedAnyHandleReadGuard<T>, AccessError> {
+        let (lock, shared) = self.detached_lock();
+        let guard = AnyHandleReadGuard::acquire(lock.read().map_err(|_| AccessError::Poisoned)?)?;
+        Ok(OwnedAnyHandleReadGuard { guard, _shared: shared })
+    }
+
+    /// Get a write guard like [write](AnyHandle::write), which holds its own
+    /// reference to the object instead of borrowing this handle.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [write](AnyHandle::write); see
+    /// [write_owned_checked](AnyHandle::write_owned_checked) for a fallible version.
+    pub fn write_owned(&self) -> OwnedAnyHandleWriteGuard<T> {
+        self.write_owned_checked().unwrap()
+    }
+
+    /// Get an owned write guard like [write_owned](AnyHandle::write_owned), or
+    /// an [AccessError] if the object cannot be accessed.
+    pub fn write_owned_checked(&self) -> Result<OwnedAnyHandleWriteGuard<T>, AccessError> {
+        let (lock, shared) = self.detached_lock();
+        let guard = AnyHandleWriteGuard::acquire(lock.write().map_err(|_| AccessError::Poisoned)?)?;
+        Ok(OwnedAnyHandleWriteGuard { guard, _shared: shared })
+    }
+}
+
+impl<T: ?Sized + 'static> OwnedAnyHandleReadGuard<T> {
+    /// Get the version of the object observed by this guard.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.guard.version()
+    }
+}
+
+impl<T: ?Sized + 'static> OwnedAnyHandleWriteGuard<T> {
+    /// Get the version of the object produced by this write.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.guard.version()
+    }
+}
+
+/// Deref a handle, immutably.
+impl<T: 'static> Deref for OwnedAnyHandleReadGuard<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+/// Deref a handle, immutably.
+impl<T: 'static> Deref for OwnedAnyHandleWriteGuard<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+/// Deref a handle, mutably.
+impl<T: 'static> DerefMut for OwnedAnyHandleWriteGuard<T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guards_outlive_their_handles() {
+        let handle = AnyHandle::from_value(vec![1u32]);
+        let other = handle.clone();
+
+        let mut guard = handle.write_owned();
+        drop(handle);
+        guard.push(2);
+        assert_eq!(guard.version(), 1);
+        drop(guard);
+
+        let guard = other.read_owned();
+        drop(other);
+        assert_eq!(*guard, [1, 2]);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{Any, AnyHandle, BuildTypeIdHasher, MappedAnyHandleReadGuard};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::sync::RwLock;
+
+type ProvideFn = for<'a> fn(&'a dyn Any, &mut Request<'a>);
+
+static PROVIDERS: RwLock<HashMap<TypeId, ProvideFn, BuildTypeIdHasher>> = RwLock::new(HashMap::with_hasher(BuildTypeIdHasher::new()));
+
+/// A value which exposes typed facets of itself, such as trait objects or
+/// configuration, to [request](AnyHandle::request) on erased handles.
+///
+/// Implementors must also be registered with [register_provider].
+///
+/// ```
+/// use any_handle::{register_provider, AnyHandle, Any, Provide, Request};
+///
+/// trait Logger { fn log(&self, message: &str) -> String; }
+///
+/// struct Prefix(String);
+/// impl Logger for Prefix {
+///     fn log(&self, message: &str) -> String { format!("{}: {message}", self.0) }
+/// }
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct Timeout(u32);
+///
+/// struct Context { logger: Prefix, timeout: Timeout }
+///
+/// impl Provide for Context {
+///     fn provide<'a>(&'a self, request: &mut Request<'a>) {
+///         request
+///             .provide_ref::<dyn Logger>(&self.logger)
+///             .provide_value(|| self.timeout.clone());
+///     }
+/// }
+///
+/// register_provider::<Context>();
+///
+/// let context = Context { logger: Prefix("db".into()), timeout: Timeout(30) };
+/// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(context).upcast();
+/// assert_eq!(handle.request::<dyn Logger>().unwrap().log("ready"), "db: ready");
+/// assert_eq!(handle.request_value::<Timeout>(), Some(Timeout(30)));
+/// assert!(handle.request_value::<String>().is_none());
+/// ```
+pub trait Provide: Any + Send + Sync {
+    /// Offer facets of this value to `request`, which keeps the first one
+    /// of the requested type.
+    fn provide<'a>(&'a self, request: &mut Request<'a>);
+}
+
+/// Marks a request for a `&D`.
+struct Ref<D: ?Sized>(PhantomData<D>);
+
+/// Marks a request for an owned `D`.
+struct Value<D>(PhantomData<D>);
+
+/// A request for one typed facet of a value, passed to [Provide::provide].
+pub struct Request<'a> {
+    /// The [TypeId] of `Ref<D>` or `Value<D>` for the requested `D`.
+    wanted: TypeId,
+    /// Points at the caller's `Option<&'a D>` or `Option<D>`, as given by `wanted`.
+    slot: NonNull<()>,
+    filled: bool,
+    // Like `&mut Option<&'a D>`, this must be invariant in `'a`.
+    marker: PhantomData<fn(&'a ()) -> &'a ()>,
+}
+
+impl<'a> Request<'a> {
+    /// Provide a reference, if a `&D` is requested and nothing has been provided yet.
+    pub fn provide_ref<D: ?Sized + 'static>(&mut self, value: &'a D) -> &mut Self {
+        if !self.filled && self.wanted == TypeId::of::<Ref<D>>() {
+            // SAFETY: `wanted` matches, so `slot` points at an `Option<&'a D>`.
+            unsafe { *self.slot.cast::<Option<&'a D>>().as_mut() = Some(value) };
+            self.filled = true;
+        }
+        self
+    }
+
+    /// Provide a value made by `f`, if a `D` is requested and nothing has
+    /// been provided yet. Otherwise, `f` is not called.
+    pub fn provide_value<D: 'static>(&mut self, f: impl FnOnce() -> D) -> &mut Self {
+        if !self.filled && self.wanted == TypeId::of::<Value<D>>() {
+            // SAFETY: `wanted` matches, so `slot` points at an `Option<D>`.
+            unsafe { *self.slot.cast::<Option<D>>().as_mut() = Some(f()) };
+            self.filled = true;
+        }
+        self
+    }
+}
+
+fn provide_erased<'a, T: Provide>(value: &'a dyn Any, request: &mut Request<'a>) {
+    value.downcast_ref::<T>().expect("provider invoked on the wrong type").provide(request);
+}
+
+/// Register `T` as a [Provide]r, so that erased handles holding a `T` can be
+/// [requested](AnyHandle::request) for its facets.
+pub fn register_provider<T: Provide>() {
+    PROVIDERS.write().unwrap().insert(TypeId::of::<T>(), provide_erased::<T>);
+}
+
+/// Ask the registered provider for `value`'s type to fill the `Option` at
+/// `slot`, whose type is given by the marker type `M`.
+fn run_provider<M: 'static>(value: &dyn Any, slot: NonNull<()>) {
+    let Some(provide) = PROVIDERS.read().unwrap().get(&value.type_id()).copied() else { return };
+    provide(value, &mut Request { wanted: TypeId::of::<M>(), slot, filled: false, marker: PhantomData });
+}
+
+fn request_ref<D: ?Sized + 'static>(value: &dyn Any) -> Option<&D> {
+    let mut slot = None;
+    run_provider::<Ref<D>>(value, NonNull::from(&mut slot).cast());
+    slot
+}
+
+fn request_value<D: 'static>(value: &dyn Any) -> Option<D> {
+    let mut slot = None;
+    run_provider::<Value<D>>(value, NonNull::from(&mut slot).cast());
+    slot
+}
+
+impl AnyHandle<dyn Any> {
+    /// Get a read guard over the `D` facet of the object, if its type is a
+    /// registered [Provide]r which offers one.
+    ///
+    /// The object stays locked for reading until the guard is dropped.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    pub fn request<D: ?Sized + 'static>(&self) -> Option<MappedAnyHandleReadGuard<'_, D>> {
+        self.read().try_map_any(request_ref)
+    }
+
+    /// Get an owned `D` facet of the object, if its type is a registered
+    /// [Provide]r which offers one. The object is only locked while the
+    /// provider runs.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    pub fn request_value<D: 'static>(&self) -> Option<D> {
+        request_value(self.read().as_any())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Settings { name: String, retries: u8 }
+
+    impl Provide for Settings {
+        fn provide<'a>(&'a self, request: &mut Request<'a>) {
+            request
+                .provide_ref::<str>(&self.name)
+                .provide_ref::<str>("shadowed")
+                .provide_value(|| self.retries)
+                .provide_value::<u16>(|| panic!("not requested"));
+        }
+    }
+
+    #[test]
+    fn first_matching_facet_wins() {
+        register_provider::<Settings>();
+        let handle = AnyHandle::from_value(Settings { name: "main".into(), retries: 2 }).upcast();
+        assert_eq!(&*handle.request::<str>().unwrap(), "main");
+        assert_eq!(handle.request_value::<u8>(), Some(2));
+        assert!(handle.request::<u8>().is_none());
+        assert!(AnyHandle::from_value(0u8).upcast().request_value::<u8>().is_none());
+    }
+}
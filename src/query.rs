@@ -0,0 +1,201 @@
+use crate::{Any, AnyHandle, BuildTypeIdHasher, MappedAnyHandleReadGuard, MappedAnyHandleWriteGuard};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+type AsRef<U> = dyn for<'r> Fn(&'r dyn Any) -> &'r U + Send + Sync;
+type AsMut<U> = dyn for<'r> Fn(&'r mut dyn Any) -> &'r mut U + Send + Sync;
+
+/// Views an erased value of one concrete type as a `U`.
+struct Caster<U: ?Sized> {
+    as_ref: Box<AsRef<U>>,
+    as_mut: Box<AsMut<U>>,
+}
+
+/// Casters keyed by (concrete type, view type), each holding an `Arc<Caster<U>>`.
+type Casters = HashMap<(TypeId, TypeId), Box<dyn Any + Send + Sync>, BuildTypeIdHasher>;
+
+static CASTERS: RwLock<Casters> = RwLock::new(HashMap::with_hasher(BuildTypeIdHasher::new()));
+
+/// Declare that values of type `C` can be viewed as the (typically unsized)
+/// type `U`, such as a trait object it implements, so erased handles holding
+/// a `C` can be [queried](AnyHandle::query) for a `U`.
+///
+/// The [register_trait!](crate::register_trait!) macro registers a concrete
+/// type with trait objects without spelling out the casts.
+pub fn register_cast<C: 'static, U: ?Sized + 'static>(as_ref: fn(&C) -> &U, as_mut: fn(&mut C) -> &mut U) {
+    let key = (TypeId::of::<C>(), TypeId::of::<U>());
+    CASTERS.write().unwrap().insert(key, Box::new(Caster::new(as_ref, as_mut)));
+}
+
+impl<U: ?Sized + 'static> Caster<U> {
+    fn new<C: 'static>(as_ref: fn(&C) -> &U, as_mut: fn(&mut C) -> &mut U) -> Arc<Self> {
+        Arc::new(Self {
+            as_ref: Box::new(move |value| as_ref(value.downcast_ref().expect("caster invoked on the wrong type"))),
+            as_mut: Box::new(move |value| as_mut(value.downcast_mut().expect("caster invoked on the wrong type"))),
+        })
+    }
+}
+
+/// Register a concrete type as implementing one or more traits, so erased
+/// handles to it can be [queried](crate::AnyHandle::query) for those trait objects.
+///
+/// ```
+/// use any_handle::{register_trait, AnyHandle, Any};
+///
+/// trait Drawable { fn draw(&self) -> String; }
+///
+/// struct Circle { radius: u32 }
+/// impl Drawable for Circle {
+///     fn draw(&self) -> String { format!("circle of radius {}", self.radius) }
+/// }
+///
+/// register_trait!(Circle: dyn Drawable);
+///
+/// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(Circle { radius: 2 }).upcast();
+/// let drawable = handle.query::<dyn Drawable>().unwrap();
+/// assert_eq!(drawable.read().draw(), "circle of radius 2");
+/// assert!(handle.query::<dyn std::fmt::Debug>().is_none());
+/// ```
+#[macro_export]
+macro_rules! register_trait {
+    ($concrete:ty: $($view:ty),+ $(,)?) => {
+        $($crate::register_cast::<$concrete, $view>(|value| value, |value| value);)+
+    };
+}
+
+/// Convert a typed handle into a [TraitHandle] to a trait object it
+/// implements, sharing the same object. This is the stable counterpart of an
+/// unsizing coercion from `AnyHandle<C>` to `AnyHandle<dyn Trait>`.
+///
+/// Unlike [query](AnyHandle::query), nothing needs registering, since the
+/// concrete type is known here.
+///
+/// ```
+/// use any_handle::{as_trait, AnyHandle, TraitHandle};
+///
+/// trait Widget { fn label(&self) -> String; }
+///
+/// struct Button;
+/// impl Widget for Button {
+///     fn label(&self) -> String { "button".into() }
+/// }
+///
+/// struct Slider(u8);
+/// impl Widget for Slider {
+///     fn label(&self) -> String { format!("slider at {}", self.0) }
+/// }
+///
+/// let slider = AnyHandle::from_value(Slider(3));
+/// let widgets: Vec<TraitHandle<dyn Widget>> = vec![
+///     as_trait!(AnyHandle::from_value(Button), dyn Widget),
+///     as_trait!(slider.clone(), dyn Widget),
+/// ];
+///
+/// slider.write().0 = 7;
+/// assert_eq!(widgets[1].read().label(), "slider at 7");
+/// ```
+#[macro_export]
+macro_rules! as_trait {
+    ($handle:expr, $view:ty) => {
+        $crate::AnyHandle::map_unsize::<$view>($handle, |value| value, |value| value)
+    };
+}
+
+/// A handle viewing its object as a `U`, typically a trait object, produced
+/// by [query](AnyHandle::query) or [map_unsize](AnyHandle::map_unsize).
+///
+/// It shares the object with the handle it was made from; guards deref to
+/// the `U` view of the object.
+pub struct TraitHandle<U: ?Sized> {
+    handle: AnyHandle<dyn Any>,
+    caster: Arc<Caster<U>>,
+}
+
+impl<U: ?Sized + 'static> TraitHandle<U> {
+    /// Get a read guard over the `U` view of the object.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [AnyHandle::read].
+    pub fn read(&self) -> MappedAnyHandleReadGuard<'_, U> {
+        self.handle.read().map_any(&*self.caster.as_ref)
+    }
+
+    /// Get a write guard over the `U` view of the object.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [AnyHandle::write].
+    pub fn write(&self) -> MappedAnyHandleWriteGuard<'_, U> {
+        self.handle.write().map_any(&*self.caster.as_mut)
+    }
+
+    /// Get back the erased handle to the object.
+    pub fn into_any(self) -> AnyHandle<dyn Any> {
+        self.handle
+    }
+}
+
+impl<U: ?Sized> Clone for TraitHandle<U> {
+    /// Make a new copy of this handle.
+    /// This will not copy the object within, and will increase the
+    /// reference count.
+    fn clone(&self) -> Self {
+        Self { handle: self.handle.clone(), caster: self.caster.clone() }
+    }
+}
+
+impl<C: 'static> AnyHandle<C> {
+    /// View the object as a `U`, typically a trait object `C` implements,
+    /// through the given casts. The [as_trait!](crate::as_trait!) macro
+    /// supplies the casts for a trait object.
+    pub fn map_unsize<U: ?Sized + 'static>(self, as_ref: fn(&C) -> &U, as_mut: fn(&mut C) -> &mut U) -> TraitHandle<U> {
+        TraitHandle { handle: self.upcast(), caster: Caster::new(as_ref, as_mut) }
+    }
+}
+
+impl AnyHandle<dyn Any> {
+    /// View the object as a `U`, if a cast from its type to `U` has been
+    /// registered with [register_cast] or [register_trait!](crate::register_trait!).
+    pub fn query<U: ?Sized + 'static>(&self) -> Option<TraitHandle<U>> {
+        let key = (self.stored_type_id(), TypeId::of::<U>());
+        let casters = CASTERS.read().unwrap();
+        let caster = casters.get(&key)?.downcast_ref::<Arc<Caster<U>>>()?.clone();
+        Some(TraitHandle { handle: self.clone(), caster })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    trait Shape { fn area(&self) -> u32; fn scale(&mut self, by: u32); }
+
+    struct Square { side: u32 }
+
+    impl Shape for Square {
+        fn area(&self) -> u32 { self.side * self.side }
+        fn scale(&mut self, by: u32) { self.side *= by; }
+    }
+
+    #[test]
+    fn queried_views_share_the_object() {
+        register_trait!(Square: dyn Shape);
+        let handle = AnyHandle::from_value(Square { side: 2 });
+        let shape = handle.clone().upcast().query::<dyn Shape>().unwrap();
+
+        shape.write().scale(3);
+        assert_eq!(shape.read().area(), 36);
+        assert_eq!(handle.read().side, 6);
+        assert!(AnyHandle::from_value(1u8).upcast().query::<dyn Shape>().is_none());
+    }
+
+    #[test]
+    fn unsized_views_share_the_object() {
+        let handle = AnyHandle::from_value(Square { side: 2 });
+        let shape = as_trait!(handle.clone(), dyn Shape);
+
+        shape.write().scale(2);
+        assert_eq!(shape.read().area(), 16);
+        assert!(shape.into_any().downcast::<Square>().unwrap().ptr_eq(&handle));
+    }
+}
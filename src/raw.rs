@@ -0,0 +1,60 @@
+use crate::{AnyHandle, Shared};
+use std::ffi::c_void;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Consume the handle, returning an opaque pointer that owns its
+    /// reference, like [Arc::into_raw]. This is meant for passing handles
+    /// through foreign code, such as a C plugin interface.
+    ///
+    /// The reference is leaked unless the pointer is handed back to
+    /// [from_raw](AnyHandle::from_raw). The pointer must not be dereferenced.
+    ///
+    /// # Example
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let handle = AnyHandle::from_value(7u32);
+    /// let raw = handle.clone().into_raw();
+    ///
+    /// // SAFETY: `raw` came from an AnyHandle<u32> and is only reclaimed once.
+    /// let restored = unsafe { AnyHandle::<u32>::from_raw(raw) };
+    /// assert!(restored.ptr_eq(&handle));
+    /// assert_eq!(*restored.read(), 7);
+    /// ```
+    pub fn into_raw(self) -> *const c_void {
+        Arc::into_raw(self.0).cast()
+    }
+
+    /// Reclaim a handle from a pointer returned by [into_raw](AnyHandle::into_raw).
+    ///
+    /// # Safety
+    /// `ptr` must have come from [into_raw](AnyHandle::into_raw), and each
+    /// such pointer may be reclaimed only once. The object must hold a `T`,
+    /// unless `T` is `dyn Any`, as with
+    /// [downcast_unchecked](AnyHandle::downcast_unchecked).
+    pub unsafe fn from_raw(ptr: *const c_void) -> Self {
+        // SAFETY: the caller guarantees `ptr` came from Arc::into_raw on a
+        // handle's shared slot and still owns that reference.
+        Self(unsafe { Arc::from_raw(ptr.cast::<Shared>()) }, PhantomData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Any, AnyHandle};
+
+    #[test]
+    fn round_trip_keeps_reference() {
+        let handle: AnyHandle<dyn Any> = AnyHandle::new(Box::new(3u8));
+        let raw = handle.clone().into_raw();
+        assert_eq!(handle.strong_count(), 2);
+
+        // SAFETY: `raw` came from into_raw and is reclaimed exactly once.
+        let restored = unsafe { AnyHandle::<dyn Any>::from_raw(raw) };
+        assert!(restored.ptr_eq(&handle));
+        drop(restored);
+        assert_eq!(handle.strong_count(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+struct Shared<T> {
+    current: RwLock<Arc<T>>,
+    /// Incremented after each new value is installed.
+    version: AtomicU64,
+    /// Serializes [update](RcuAnyHandle::update)s, so none are lost.
+    writer: Mutex<()>,
+}
+
+/// A shared value for read-mostly workloads, which is replaced rather than
+/// mutated in place, in the style of read-copy-update.
+///
+/// Each reader thread takes an [RcuReader] with [reader](Self::reader),
+/// whose [load](RcuReader::load) is a single atomic load while the value is
+/// unchanged, taking no lock. Only the first load after a new value is
+/// installed takes a read lock, briefly, to fetch it, and may wait for a
+/// writer installing a value at the same moment. Writers install a new value
+/// with [store](Self::store) or [update](Self::update); snapshots taken
+/// earlier keep the old one.
+///
+/// Clones share the same value.
+///
+/// # Example
+/// ```
+/// use any_handle::RcuAnyHandle;
+///
+/// let routes = RcuAnyHandle::new(vec!["/"]);
+/// let mut reader = routes.reader();
+/// assert_eq!(reader.load().len(), 1);
+///
+/// routes.update(|routes| [routes.as_slice(), &["/about"]].concat());
+/// assert_eq!(*reader.load().as_slice(), ["/", "/about"]);
+/// ```
+pub struct RcuAnyHandle<T>(Arc<Shared<T>>);
+
+/// A reader of an [RcuAnyHandle], caching the last value it loaded.
+pub struct RcuReader<T> {
+    shared: Arc<Shared<T>>,
+    cached: Arc<T>,
+    version: u64,
+}
+
+impl<T> RcuAnyHandle<T> {
+    /// Create a handle to `value`.
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(Shared {
+            current: RwLock::new(Arc::new(value)),
+            version: AtomicU64::new(0),
+            writer: Mutex::new(()),
+        }))
+    }
+
+    /// Get a snapshot of the current value.
+    ///
+    /// This briefly takes a lock to clone an [Arc]; readers which load often
+    /// should use a [reader](Self::reader) instead.
+    pub fn load(&self) -> Arc<T> {
+        self.0.current.read().unwrap().clone()
+    }
+
+    /// Get a reader for loading the value without locking while it is unchanged.
+    pub fn reader(&self) -> RcuReader<T> {
+        let version = self.0.version.load(Ordering::Acquire);
+        RcuReader { shared: self.0.clone(), cached: self.load(), version }
+    }
+
+    /// Install `value`, returning the value it replaces.
+    pub fn store(&self, value: T) -> Arc<T> {
+        let _writer = self.0.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        self.install(Arc::new(value))
+    }
+
+    /// Install the value returned by `f` for the current one. Concurrent
+    /// updates are applied one after another, so none are lost. If `f`
+    /// panics, the current value is kept and later updates proceed normally.
+    pub fn update(&self, f: impl FnOnce(&T) -> T) {
+        let _writer = self.0.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let next = f(&self.load());
+        self.install(Arc::new(next));
+    }
+
+    /// Get the number of values installed since this handle was created.
+    pub fn version(&self) -> u64 {
+        self.0.version.load(Ordering::Acquire)
+    }
+
+    fn install(&self, value: Arc<T>) -> Arc<T> {
+        let old = std::mem::replace(&mut *self.0.current.write().unwrap(), value);
+        // Published only once the value is in place, so a reader which sees
+        // the new version also loads the new value.
+        self.0.version.fetch_add(1, Ordering::Release);
+        old
+    }
+}
+
+impl<T> RcuReader<T> {
+    /// Get the current value, reloading it only if a new one was installed
+    /// since the last load. Only a reload takes a lock.
+    pub fn load(&mut self) -> &Arc<T> {
+        let version = self.shared.version.load(Ordering::Acquire);
+        if version != self.version {
+            self.cached = self.shared.current.read().unwrap().clone();
+            self.version = version;
+        }
+        &self.cached
+    }
+}
+
+impl<T> Clone for RcuAnyHandle<T> {
+    /// Make a new handle to the same value.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Clone for RcuReader<T> {
+    /// Make a new reader, starting from the value this one last loaded.
+    fn clone(&self) -> Self {
+        Self { shared: self.shared.clone(), cached: self.cached.clone(), version: self.version }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readers_observe_new_values() {
+        let handle = RcuAnyHandle::new(0u32);
+        let mut reader = handle.reader();
+        let snapshot = reader.load().clone();
+
+        let writers: Vec<_> = (0..4)
+            .map(|_| {
+                let handle = handle.clone();
+                std::thread::spawn(move || (0..25).for_each(|_| handle.update(|value| value + 1)))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!((**reader.load(), *snapshot), (100, 0));
+        assert_eq!(*handle.store(7), 100);
+        assert_eq!((**reader.load(), handle.version()), (7, 101));
+
+        let panicked = std::panic::catch_unwind(|| handle.update(|_| panic!("failed update")));
+        assert!(panicked.is_err());
+        handle.update(|value| value + 1);
+        assert_eq!(**reader.load(), 8);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{AccessError, AnyHandle, AnyHandleReadGuard};
+
+/// A handle to the object of an [AnyHandle] which can only read it, made with
+/// [read_only](AnyHandle::read_only).
+///
+/// It shares the object with the handle it was made from, and sees its
+/// writes, but has no way to write or to get a writable handle back, so it
+/// can be given to code which should only observe the object.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, ReadOnlyAnyHandle};
+///
+/// fn render(scores: ReadOnlyAnyHandle<Vec<u32>>) -> String {
+///     format!("{:?}", *scores.read())
+/// }
+///
+/// let scores = AnyHandle::from_value(vec![3]);
+/// let view = scores.read_only();
+/// scores.write().push(5);
+/// assert_eq!(render(view), "[3, 5]");
+/// ```
+pub struct ReadOnlyAnyHandle<T: ?Sized>(AnyHandle<T>);
+
+impl<T: ?Sized> ReadOnlyAnyHandle<T> {
+    /// Get a read guard over the object.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [AnyHandle::read].
+    #[inline(always)]
+    pub fn read(&self) -> AnyHandleReadGuard<'_, T> {
+        self.0.read()
+    }
+
+    /// Get a read guard like [read](Self::read), or an [AccessError] if the
+    /// object cannot be accessed.
+    #[inline(always)]
+    pub fn read_checked(&self) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        self.0.read_checked()
+    }
+
+    /// Get a read guard without blocking, or [AccessError::WouldBlock] if a
+    /// write guard currently exists for the object.
+    #[inline(always)]
+    pub fn try_read(&self) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        self.0.try_read()
+    }
+
+    /// Get the current version of the object, see [AnyHandle::version].
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.0.version()
+    }
+
+    /// Check whether this handle refers to the same object as `other`.
+    #[inline(always)]
+    pub fn ptr_eq<U: ?Sized>(&self, other: &AnyHandle<U>) -> bool {
+        self.0.ptr_eq(other)
+    }
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Make a [ReadOnlyAnyHandle] to this handle's object.
+    pub fn read_only(&self) -> ReadOnlyAnyHandle<T> {
+        ReadOnlyAnyHandle(self.clone())
+    }
+}
+
+impl<T: ?Sized> From<AnyHandle<T>> for ReadOnlyAnyHandle<T> {
+    /// Give up the ability to write through `handle`.
+    fn from(handle: AnyHandle<T>) -> Self {
+        Self(handle)
+    }
+}
+
+impl<T: ?Sized> Clone for ReadOnlyAnyHandle<T> {
+    /// Make a new read-only handle to the same object.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_see_writes_through_the_handle() {
+        let handle = AnyHandle::from_value(1u32);
+        let view = ReadOnlyAnyHandle::from(handle.clone());
+        let guard = handle.write();
+        assert_eq!(view.try_read().err(), Some(AccessError::WouldBlock));
+        drop(guard);
+
+        *handle.write() = 2;
+        assert_eq!((*view.read(), view.version()), (2, 2));
+        assert!(view.clone().ptr_eq(&handle));
+    }
+}
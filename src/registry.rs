@@ -0,0 +1,396 @@
+use crate::{Any, AnyHandle};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::ThreadId;
+
+/// A name under which a value of type `T` is published in a [HandleRegistry].
+///
+/// Looking a key up checks the value type at compile time, so call sites
+/// never have to guess what a name should be downcast to.
+///
+/// # Example
+/// ```
+/// use any_handle::{HandleRegistry, Key};
+///
+/// struct Config { verbose: bool }
+///
+/// const CONFIG: Key<Config> = Key::new("config");
+///
+/// let registry = HandleRegistry::new();
+/// registry.insert_value(CONFIG, Config { verbose: true });
+/// assert!(registry.get(CONFIG).unwrap().read().verbose);
+/// ```
+pub struct Key<T: ?Sized> {
+    name: &'static str,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: ?Sized> Key<T> {
+    /// Create a key for values of type `T` published under `name`.
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, marker: PhantomData }
+    }
+
+    /// Get the name this key refers to.
+    #[inline(always)]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: ?Sized> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for Key<T> {}
+
+/// Anything that can name an entry of type `T` in a [HandleRegistry]:
+/// either a typed [Key] or a plain string, in which case the value type
+/// must be given explicitly.
+pub trait RegistryKey<T: ?Sized> {
+    /// Get the name of the entry.
+    fn name(&self) -> &str;
+}
+
+impl<T: ?Sized> RegistryKey<T> for Key<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+}
+
+impl<T: ?Sized> RegistryKey<T> for &str {
+    fn name(&self) -> &str {
+        self
+    }
+}
+
+type Namespaces = HashMap<String, HashMap<String, AnyHandle<dyn Any>>>;
+
+/// A handle temporarily shadowing an entry for a single thread.
+struct Override {
+    id: u64,
+    path: String,
+    name: String,
+    handle: AnyHandle<dyn Any>,
+}
+
+#[derive(Default)]
+struct Shared {
+    namespaces: RwLock<Namespaces>,
+    overrides: RwLock<HashMap<ThreadId, Vec<Override>>>,
+    next_override: AtomicU64,
+}
+
+/// A thread-safe registry of handles published under string names.
+///
+/// Registries are organized into namespaces: [child](Self::child) returns a
+/// registry for a nested namespace which shares storage with its parent.
+/// Lookups in a child fall back to its ancestors on a miss, so entries
+/// published closer to the root act as globals which a child may shadow.
+///
+/// Cloning a registry is cheap and yields another view of the same namespace.
+///
+/// Entries may also be replaced temporarily, for the current thread only, with
+/// [override_scoped](Self::override_scoped).
+///
+/// Lookups check the stored type without locking the object, so they neither
+/// wait for a writer nor run the initializer of a
+/// [lazy](AnyHandle::new_lazy) handle.
+///
+/// # Example
+/// ```
+/// use any_handle::HandleRegistry;
+///
+/// struct Gravity(f32);
+///
+/// let registry = HandleRegistry::new();
+/// registry.insert_value("gravity", Gravity(9.8));
+///
+/// let moon = registry.child("moon");
+/// assert_eq!(moon.get::<Gravity>("gravity").unwrap().read().0, 9.8);
+///
+/// moon.insert_value("gravity", Gravity(1.6));
+/// assert_eq!(moon.get::<Gravity>("gravity").unwrap().read().0, 1.6);
+/// assert_eq!(registry.get::<Gravity>("gravity").unwrap().read().0, 9.8);
+/// ```
+#[derive(Clone)]
+pub struct HandleRegistry {
+    shared: Arc<Shared>,
+    path: String,
+}
+
+impl HandleRegistry {
+    /// Separates nested namespace names in [namespace](Self::namespace).
+    pub const SEPARATOR: char = '.';
+
+    /// Create an empty root registry.
+    pub fn new() -> Self {
+        let shared = Shared::default();
+        shared.namespaces.write().unwrap().insert(String::new(), HashMap::new());
+        Self { shared: Arc::new(shared), path: String::new() }
+    }
+
+    /// Get the registry for the namespace `name` nested within this one,
+    /// creating it if it does not exist yet.
+    ///
+    /// # Panics
+    /// Panics if `name` is empty or contains [SEPARATOR](Self::SEPARATOR),
+    /// which would make the namespace indistinguishable from another one;
+    /// call `child` once per level to nest deeper.
+    pub fn child(&self, name: &str) -> HandleRegistry {
+        assert!(!name.is_empty(), "namespace names must not be empty");
+        assert!(!name.contains(Self::SEPARATOR), "namespace name {name:?} contains the separator {:?}", Self::SEPARATOR);
+        let path = if self.path.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}{}{}", self.path, Self::SEPARATOR, name)
+        };
+        self.shared.namespaces.write().unwrap().entry(path.clone()).or_default();
+        Self { shared: self.shared.clone(), path }
+    }
+
+    /// Get the registry of the enclosing namespace, or [None] for the root.
+    pub fn parent(&self) -> Option<HandleRegistry> {
+        let path = self.path.rsplit_once(Self::SEPARATOR).map_or("", |(parent, _)| parent);
+        (!self.path.is_empty()).then(|| Self { shared: self.shared.clone(), path: path.to_owned() })
+    }
+
+    /// Get the full name of this registry's namespace, which is empty for the root.
+    #[inline(always)]
+    pub fn namespace(&self) -> &str {
+        &self.path
+    }
+
+    /// Iterate over this namespace and its ancestors, innermost first.
+    fn lookup_path(&self) -> impl Iterator<Item = &str> {
+        let ancestors = self.path.rmatch_indices(Self::SEPARATOR).map(|(i, _)| &self.path[..i]);
+        std::iter::once(self.path.as_str())
+            .chain(ancestors)
+            .chain((!self.path.is_empty()).then_some(""))
+    }
+
+    /// Find the entry `name`, searching the namespaces in `paths` in order.
+    fn find<'a>(&self, mut paths: impl Iterator<Item = &'a str>, name: &str) -> Option<AnyHandle<dyn Any>> {
+        let overrides = self.shared.overrides.read().unwrap();
+        let overrides = overrides.get(&std::thread::current().id()).map_or(&[][..], Vec::as_slice);
+        let namespaces = self.shared.namespaces.read().unwrap();
+        paths
+            .find_map(|path| {
+                let overridden = overrides.iter().rev().find(|o| o.path == path && o.name == name);
+                overridden.map(|o| &o.handle).or_else(|| namespaces.get(path)?.get(name))
+            })
+            .cloned()
+    }
+
+    /// Publish a handle under `name` in this namespace.
+    /// Returns the handle previously published under that name here, if any.
+    pub fn insert(&self, name: impl Into<String>, handle: AnyHandle<dyn Any>) -> Option<AnyHandle<dyn Any>> {
+        let mut namespaces = self.shared.namespaces.write().unwrap();
+        namespaces.entry(self.path.clone()).or_default().insert(name.into(), handle)
+    }
+
+    /// Wrap `value` in a new handle and publish it under `key` in this namespace.
+    /// Returns the handle previously published under that name here, if any.
+    pub fn insert_value<T: Any + Send + Sync>(&self, key: impl RegistryKey<T>, value: T) -> Option<AnyHandle<dyn Any>> {
+        self.insert(key.name(), AnyHandle::from_value(value).upcast())
+    }
+
+    /// Remove the handle published under `name` in this namespace, returning it.
+    /// Entries of the same name in ancestor namespaces are left in place, and
+    /// become visible from this namespace again.
+    pub fn remove(&self, name: &str) -> Option<AnyHandle<dyn Any>> {
+        let mut namespaces = self.shared.namespaces.write().unwrap();
+        namespaces.get_mut(&self.path)?.remove(name)
+    }
+
+    /// Get the handle published under `key` in this namespace or the nearest
+    /// ancestor that has one, if it holds a `T`.
+    pub fn get<T: 'static>(&self, key: impl RegistryKey<T>) -> Option<AnyHandle<T>> {
+        self.find(self.lookup_path(), key.name())?.downcast().ok()
+    }
+
+    /// Get the handle published under `key` in this namespace only, if it holds a `T`.
+    pub fn get_local<T: 'static>(&self, key: impl RegistryKey<T>) -> Option<AnyHandle<T>> {
+        self.find(std::iter::once(self.path.as_str()), key.name())?.downcast().ok()
+    }
+
+    /// Check whether any handle is published under `name` in this namespace
+    /// or one of its ancestors.
+    pub fn contains(&self, name: &str) -> bool {
+        self.find(self.lookup_path(), name).is_some()
+    }
+
+    /// Shadow the entry `name` in this namespace with `handle`, for lookups
+    /// made from the current thread only, until the returned guard is dropped.
+    ///
+    /// Other threads keep seeing the original entry, which makes this suitable
+    /// for injecting fakes into tests that run in parallel against a shared
+    /// registry. Overrides nest: the most recent live override wins.
+    ///
+    /// Overrides are keyed by thread, not by task: an async task which moves
+    /// to another worker thread stops seeing them, and other tasks running on
+    /// this thread see them too.
+    pub fn override_scoped(&self, name: impl Into<String>, handle: AnyHandle<dyn Any>) -> OverrideGuard {
+        let id = self.shared.next_override.fetch_add(1, Ordering::Relaxed);
+        let thread = std::thread::current().id();
+        let entry = Override { id, path: self.path.clone(), name: name.into(), handle };
+        self.shared.overrides.write().unwrap().entry(thread).or_default().push(entry);
+        OverrideGuard { shared: self.shared.clone(), thread, id }
+    }
+
+    /// Wrap `value` in a new handle and use it to shadow `key` for the
+    /// current thread, as with [override_scoped](Self::override_scoped).
+    pub fn override_value_scoped<T: Any + Send + Sync>(&self, key: impl RegistryKey<T>, value: T) -> OverrideGuard {
+        self.override_scoped(key.name(), AnyHandle::from_value(value).upcast())
+    }
+
+    /// List the names published directly in this namespace.
+    pub fn names(&self) -> Vec<String> {
+        let namespaces = self.shared.namespaces.read().unwrap();
+        namespaces.get(&self.path).map_or_else(Vec::new, |entries| entries.keys().cloned().collect())
+    }
+
+    /// List the names and handles published directly in this namespace.
+    pub fn entries(&self) -> Vec<(String, AnyHandle<dyn Any>)> {
+        let namespaces = self.shared.namespaces.read().unwrap();
+        namespaces.get(&self.path).map_or_else(Vec::new, |entries| {
+            entries.iter().map(|(name, handle)| (name.clone(), handle.clone())).collect()
+        })
+    }
+
+    /// List the names of the namespaces nested directly within this one.
+    pub fn children(&self) -> Vec<String> {
+        let namespaces = self.shared.namespaces.read().unwrap();
+        namespaces
+            .keys()
+            .filter_map(|path| {
+                let rest = if self.path.is_empty() {
+                    Some(path.as_str()).filter(|rest| !rest.is_empty())
+                } else {
+                    path.strip_prefix(&self.path)?.strip_prefix(Self::SEPARATOR)
+                }?;
+                (!rest.contains(Self::SEPARATOR)).then(|| rest.to_owned())
+            })
+            .collect()
+    }
+}
+
+impl Default for HandleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Restores the entry shadowed by [HandleRegistry::override_scoped] when dropped.
+#[must_use = "the override is removed as soon as the guard is dropped"]
+pub struct OverrideGuard {
+    shared: Arc<Shared>,
+    thread: ThreadId,
+    id: u64,
+}
+
+impl Drop for OverrideGuard {
+    fn drop(&mut self) {
+        let mut overrides = self.shared.overrides.write().unwrap();
+        if let Some(stack) = overrides.get_mut(&self.thread) {
+            stack.retain(|o| o.id != self.id);
+            if stack.is_empty() {
+                overrides.remove(&self.thread);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Config { level: u8 }
+
+    const CONFIG: Key<Config> = Key::new("config");
+
+    #[test]
+    fn typed_and_string_lookup() {
+        let registry = HandleRegistry::new();
+        registry.insert_value(CONFIG, Config { level: 3 });
+
+        assert_eq!(registry.get(CONFIG).unwrap().read().level, 3);
+        assert_eq!(registry.get::<Config>("config").unwrap().read().level, 3);
+        assert!(registry.get::<u32>("config").is_none());
+        assert!(registry.get(Key::<Config>::new("missing")).is_none());
+        assert!(registry.contains(CONFIG.name()));
+
+        let config = registry.get(CONFIG).unwrap();
+        let _guard = config.write();
+        assert!(registry.get(CONFIG).is_some());
+    }
+
+    #[test]
+    #[should_panic(expected = "contains the separator")]
+    fn nested_names_are_rejected() {
+        HandleRegistry::new().child("physics.collision");
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be empty")]
+    fn empty_names_are_rejected() {
+        HandleRegistry::new().child("physics").child("");
+    }
+
+    #[test]
+    fn child_namespaces() {
+        let registry = HandleRegistry::new();
+        registry.insert_value(CONFIG, Config { level: 1 });
+
+        let physics = registry.child("physics");
+        let collision = physics.child("collision");
+        assert_eq!(collision.namespace(), "physics.collision");
+        assert_eq!(collision.parent().unwrap().namespace(), "physics");
+        assert!(registry.parent().is_none());
+
+        assert_eq!(collision.get(CONFIG).unwrap().read().level, 1);
+        assert!(collision.get_local(CONFIG).is_none());
+
+        physics.insert_value(CONFIG, Config { level: 2 });
+        assert_eq!(collision.get(CONFIG).unwrap().read().level, 2);
+        assert_eq!(registry.get(CONFIG).unwrap().read().level, 1);
+
+        assert_eq!(registry.children(), vec!["physics".to_owned()]);
+        assert_eq!(physics.children(), vec!["collision".to_owned()]);
+        assert_eq!(physics.names(), vec!["config".to_owned()]);
+        assert!(collision.names().is_empty());
+        assert_eq!(registry.child("physics").get_local(CONFIG).unwrap().read().level, 2);
+
+        let (name, handle) = physics.entries().pop().unwrap();
+        assert_eq!(name, "config");
+        assert!(physics.remove("config").unwrap().ptr_eq(&handle));
+        assert!(physics.remove("config").is_none());
+        assert_eq!(collision.get(CONFIG).unwrap().read().level, 1);
+    }
+
+    #[test]
+    fn scoped_overrides() {
+        let registry = HandleRegistry::new();
+        registry.insert_value(CONFIG, Config { level: 1 });
+        let child = registry.child("child");
+
+        {
+            let _outer = registry.override_value_scoped(CONFIG, Config { level: 2 });
+            assert_eq!(child.get(CONFIG).unwrap().read().level, 2);
+            {
+                let _inner = registry.override_value_scoped(CONFIG, Config { level: 3 });
+                assert_eq!(registry.get(CONFIG).unwrap().read().level, 3);
+            }
+            assert_eq!(registry.get(CONFIG).unwrap().read().level, 2);
+
+            std::thread::scope(|scope| {
+                scope.spawn(|| assert_eq!(registry.get(CONFIG).unwrap().read().level, 1));
+            });
+        }
+        assert_eq!(registry.get(CONFIG).unwrap().read().level, 1);
+    }
+}
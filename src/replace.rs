@@ -0,0 +1,107 @@
+use crate::watch::SlotWriteGuard;
+use crate::{Any, AnyHandle, Contents};
+use std::sync::Arc;
+
+impl<T: 'static> AnyHandle<T> {
+    /// Replace the object with `value`, returning the old value. Every clone
+    /// of this handle observes the new value.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let level = AnyHandle::from_value(String::from("intro"));
+    /// let viewer = level.clone();
+    ///
+    /// assert_eq!(level.replace(String::from("boss")), "intro");
+    /// assert_eq!(*viewer.read(), "boss");
+    /// assert_eq!(viewer.take(), "boss");
+    /// assert_eq!(*level.read(), "");
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as writing through [write](AnyHandle::write).
+    pub fn replace(&self, value: T) -> T {
+        std::mem::replace(&mut *self.write(), value)
+    }
+
+    /// Replace the object with its default value, returning the old value.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as writing through [write](AnyHandle::write).
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+
+    /// Swap the objects of two handles. Both are locked, in the same order
+    /// as [lock_many](crate::lock_many), so concurrent swaps cannot deadlock.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as writing through [write](AnyHandle::write).
+    pub fn swap(&self, other: &AnyHandle<T>) {
+        if self.ptr_eq(other) {
+            return;
+        }
+        let (first, second) = if Arc::as_ptr(&self.0) < Arc::as_ptr(&other.0) { (self, other) } else { (other, self) };
+        let mut first = first.write();
+        // Release the first lock before panicking, rather than poison it.
+        let mut second = match second.write_checked() {
+            Ok(second) => second,
+            Err(error) => {
+                drop(first);
+                panic!("{error}");
+            }
+        };
+        std::mem::swap(&mut *first, &mut *second);
+    }
+}
+
+impl AnyHandle<dyn Any> {
+    /// Replace the object with `value`, returning the old one, if `value` has
+    /// the same type as the stored object. Otherwise, or if the handle has been
+    /// [revoked](AnyHandle::revoke), is [read-only](AnyHandle::is_read_only) or
+    /// is [poisoned](AnyHandle::is_poisoned), returns Err(value).
+    pub fn replace_boxed(&self, value: Box<dyn Any + Send + Sync>) -> Result<Box<dyn Any + Send + Sync>, Box<dyn Any + Send + Sync>> {
+        let Ok(slot) = self.0.write() else {
+            return Err(value);
+        };
+        let mut slot = SlotWriteGuard::new(slot);
+        if slot.value.writable().is_err() || (*slot.value.get()).type_id() != (*value).type_id() {
+            return Err(value);
+        }
+        slot.version += 1;
+        // Promotes borrowed values to owned ones.
+        slot.value.get_mut();
+        match std::mem::replace(&mut slot.value, Contents::Owned(value)) {
+            Contents::Owned(old) => Ok(old),
+            Contents::Static(..) | Contents::Forked(..) | Contents::Lazy(..) | Contents::Revoked => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swapping_and_boxed_replacement() {
+        let a = AnyHandle::from_value(1u32);
+        let b = AnyHandle::from_value(2u32);
+        a.swap(&b);
+        b.swap(&b);
+        assert_eq!((*a.read(), *b.read()), (2, 1));
+
+        let erased = a.clone().upcast();
+        assert!(erased.replace_boxed(Box::new("text")).is_err());
+        let old = erased.replace_boxed(Box::new(5u32)).ok().unwrap();
+        assert_eq!(old.downcast_ref::<u32>(), Some(&2));
+        assert_eq!((*a.read(), a.version()), (5, 2));
+
+        static CONSTANT: u32 = 7;
+        let constant = AnyHandle::from_static(&CONSTANT);
+        assert!(constant.replace_boxed(Box::new(8u32)).is_err());
+        assert_eq!(*constant.downcast::<u32>().ok().unwrap().read(), 7);
+    }
+}
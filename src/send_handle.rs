@@ -0,0 +1,153 @@
+use crate::{AccessError, Any};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+
+type SendErased = dyn Any + Send;
+
+/// A shared pointer to a value which is [Send] but not necessarily [Sync],
+/// allowing for downcasting.
+///
+/// Where [AnyHandle](crate::AnyHandle) requires stored values to be
+/// `Send + Sync` so that many threads may read them at once, this handle uses
+/// a [Mutex], so only one thread can access the value at a time. It is
+/// therefore sound to store values such as channel senders or cells, and the
+/// handle itself is still `Send + Sync` — only its guards are tied to a thread.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnySendHandle, Any};
+/// use std::cell::Cell;
+///
+/// let handle: AnySendHandle<dyn Any> = AnySendHandle::new(Box::new(Cell::new(1)));
+/// let handle: AnySendHandle<Cell<i32>> = handle.downcast().ok().unwrap();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| handle.lock().set(2));
+/// });
+/// assert_eq!(handle.lock().get(), 2);
+/// ```
+pub struct AnySendHandle<T: ?Sized>(Arc<Mutex<Box<SendErased>>>, PhantomData<fn(T) -> T>);
+
+impl AnySendHandle<dyn Any> {
+    /// Initialize an AnySendHandle from a [Box]<dyn [Any] + [Send]>.
+    pub fn new(inner: Box<dyn Any + Send>) -> Self {
+        Self(Arc::new(Mutex::new(inner)), PhantomData)
+    }
+
+    /// Downcast this handle from `dyn Any` to a specific type.
+    /// If the stored data can be downcast to type Y, succeeds and
+    /// returns Ok(the cast handle).
+    /// If the data cannot be downcast, errors and returns Error(self).
+    pub fn downcast<Y: 'static>(self) -> Result<AnySendHandle<Y>, Self> {
+        if self.0.lock().unwrap_or_else(PoisonError::into_inner).is::<Y>() {
+            Ok(AnySendHandle(self.0, PhantomData))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T: ?Sized> AnySendHandle<T> {
+    /// Get a guard giving exclusive access to the object, blocking until
+    /// no other guard exists for it.
+    ///
+    /// # Panics
+    /// Panics if the object is poisoned, which happens when a thread panics
+    /// while holding a guard for it; see [lock_checked](Self::lock_checked)
+    /// for a fallible version.
+    #[inline(always)]
+    pub fn lock(&self) -> AnySendHandleGuard<'_, T> {
+        self.lock_checked().unwrap()
+    }
+
+    /// Get a guard like [lock](Self::lock), or [AccessError::Poisoned] if the
+    /// object is poisoned.
+    pub fn lock_checked(&self) -> Result<AnySendHandleGuard<'_, T>, AccessError> {
+        let guard = self.0.lock().map_err(|_| AccessError::Poisoned)?;
+        Ok(AnySendHandleGuard(guard, PhantomData))
+    }
+
+    /// Get a guard without blocking, or [AccessError::WouldBlock] if another
+    /// guard currently exists for the object.
+    pub fn try_lock(&self) -> Result<AnySendHandleGuard<'_, T>, AccessError> {
+        match self.0.try_lock() {
+            Ok(guard) => Ok(AnySendHandleGuard(guard, PhantomData)),
+            Err(TryLockError::WouldBlock) => Err(AccessError::WouldBlock),
+            Err(TryLockError::Poisoned(_)) => Err(AccessError::Poisoned),
+        }
+    }
+
+    /// Get a count of the number of living references to this object.
+    #[inline(always)]
+    pub fn reference_count(&self) -> usize {
+        Arc::strong_count(&self.0)
+    }
+}
+
+impl<T: ?Sized> Clone for AnySendHandle<T> {
+    /// Make a new copy of this handle.
+    /// This will not copy the object within, and will increase the
+    /// reference count.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+/// Exclusive access to the object behind an [AnySendHandle].
+/// Only one guard may exist for the same object at a given time.
+pub struct AnySendHandleGuard<'a, T: ?Sized + 'a>(MutexGuard<'a, Box<SendErased>>, PhantomData<fn(T) -> T>);
+
+/// Deref a handle, immutably.
+impl<'a, T: 'a + 'static> Deref for AnySendHandleGuard<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        unsafe { &*(self.0.deref().deref() as *const SendErased as *const T) }
+    }
+}
+
+/// Deref a handle, mutably.
+impl<'a, T: 'a + 'static> DerefMut for AnySendHandleGuard<'a, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *(self.0.deref_mut().deref_mut() as *mut SendErased as *mut T) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn auto_traits() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<AnySendHandle<dyn Any>>();
+        assert_send_sync::<AnySendHandle<mpsc::Sender<u8>>>();
+    }
+
+    #[test]
+    fn stores_send_only_values() {
+        let (sender, receiver) = mpsc::channel::<u8>();
+        let handle = AnySendHandle::new(Box::new(sender)).downcast::<mpsc::Sender<u8>>().ok().unwrap();
+        let other = handle.clone();
+
+        std::thread::spawn(move || other.lock().send(7).unwrap()).join().unwrap();
+        assert_eq!(receiver.recv().unwrap(), 7);
+
+        let guard = handle.lock();
+        assert_eq!(handle.try_lock().err(), Some(AccessError::WouldBlock));
+        drop(guard);
+        assert_eq!(handle.reference_count(), 1);
+
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = handle.lock();
+            panic!("interrupted");
+        });
+        assert_eq!(handle.try_lock().err(), Some(AccessError::Poisoned));
+        assert_eq!(handle.lock_checked().err(), Some(AccessError::Poisoned));
+    }
+}
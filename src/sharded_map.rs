@@ -0,0 +1,198 @@
+use crate::{Any, AnyHandle, BuildTypeIdHasher};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::sync::RwLock;
+
+type Shard<S> = RwLock<HashMap<TypeId, AnyHandle<dyn Any>, S>>;
+
+/// A concurrent map of handles keyed by the [TypeId] of their stored value.
+///
+/// Entries are spread across a number of independently locked shards, so
+/// inserts and lookups of different types from many threads rarely contend
+/// on the same lock.
+///
+/// Keys are hashed with `S`, which defaults to the pass-through
+/// [BuildTypeIdHasher]; any other [BuildHasher] may be supplied through
+/// [with_hasher](Self::with_hasher).
+///
+/// # Example
+/// ```
+/// use any_handle::ShardedAnyHandleMap;
+///
+/// struct Renderer { frames: u32 }
+///
+/// let map = ShardedAnyHandleMap::new();
+/// map.insert_value(Renderer { frames: 0 });
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| map.get::<Renderer>().unwrap().write().frames += 1);
+/// });
+///
+/// assert_eq!(map.get::<Renderer>().unwrap().read().frames, 1);
+/// ```
+pub struct ShardedAnyHandleMap<S = BuildTypeIdHasher> {
+    shards: Box<[Shard<S>]>,
+    hasher: S,
+}
+
+impl ShardedAnyHandleMap {
+    /// Create an empty map with a shard count suited to the available parallelism.
+    pub fn new() -> Self {
+        Self::with_hasher(BuildTypeIdHasher::default())
+    }
+
+    /// Create an empty map with exactly `shards` shards.
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, BuildTypeIdHasher::default())
+    }
+}
+
+impl<S: BuildHasher + Clone> ShardedAnyHandleMap<S> {
+    /// Create an empty map that hashes keys with `hasher`.
+    pub fn with_hasher(hasher: S) -> Self {
+        let parallelism = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_shards_and_hasher((parallelism * 4).next_power_of_two(), hasher)
+    }
+
+    /// Create an empty map with exactly `shards` shards that hashes keys with `hasher`.
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards_and_hasher(shards: usize, hasher: S) -> Self {
+        assert!(shards > 0, "a ShardedAnyHandleMap needs at least one shard");
+        Self {
+            shards: (0..shards).map(|_| RwLock::new(HashMap::with_hasher(hasher.clone()))).collect(),
+            hasher,
+        }
+    }
+
+    /// Get the number of shards in this map.
+    #[inline(always)]
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn shard(&self, key: TypeId) -> &Shard<S> {
+        // Use the high bits, as each shard's table indexes by the low bits.
+        let index = (self.hasher.hash_one(key) >> 32) as usize % self.shards.len();
+        &self.shards[index]
+    }
+
+    /// Insert a handle, keyed by the type of its stored value.
+    /// Returns the handle previously stored for that type, if any.
+    pub fn insert(&self, handle: AnyHandle<dyn Any>) -> Option<AnyHandle<dyn Any>> {
+        let key = handle.stored_type_id();
+        self.shard(key).write().unwrap().insert(key, handle)
+    }
+
+    /// Wrap `value` in a new handle and insert it.
+    /// Returns the handle previously stored for that type, if any.
+    pub fn insert_value<T: Any + Send + Sync>(&self, value: T) -> Option<AnyHandle<dyn Any>> {
+        self.insert(AnyHandle::from_value(value).upcast())
+    }
+
+    /// Get a typed handle to the stored value of type `T`, if present.
+    pub fn get<T: 'static>(&self) -> Option<AnyHandle<T>> {
+        let key = TypeId::of::<T>();
+        let shard = self.shard(key).read().unwrap();
+        shard.get(&key).map(|handle| handle.clone().cast_unchecked())
+    }
+
+    /// Get the handle stored for type `T`, inserting the result of `init` first if absent.
+    ///
+    /// The check and insertion happen under a single shard lock, so concurrent
+    /// callers agree on one value and `init` runs at most once per insertion.
+    pub fn get_or_insert_with<T: Any + Send + Sync>(&self, init: impl FnOnce() -> T) -> AnyHandle<T> {
+        let key = TypeId::of::<T>();
+        let mut shard = self.shard(key).write().unwrap();
+        shard
+            .entry(key)
+            .or_insert_with(|| AnyHandle::from_value(init()).upcast())
+            .clone()
+            .cast_unchecked()
+    }
+
+    /// Remove and return the handle stored for type `T`, if present.
+    pub fn remove<T: 'static>(&self) -> Option<AnyHandle<T>> {
+        let key = TypeId::of::<T>();
+        let handle = self.shard(key).write().unwrap().remove(&key);
+        handle.map(AnyHandle::cast_unchecked)
+    }
+
+    /// Check whether a value of type `T` is stored.
+    pub fn contains<T: 'static>(&self) -> bool {
+        let key = TypeId::of::<T>();
+        self.shard(key).read().unwrap().contains_key(&key)
+    }
+
+    /// Count the stored handles. Each shard is locked in turn, so the result
+    /// may be stale if other threads are modifying the map concurrently.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(|shard| shard.read().unwrap().len()).sum()
+    }
+
+    /// Check whether the map holds no handles.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(|shard| shard.read().unwrap().is_empty())
+    }
+
+    /// Remove all handles from the map.
+    pub fn clear(&self) {
+        for shard in self.shards.iter() {
+            shard.write().unwrap().clear();
+        }
+    }
+}
+
+impl Default for ShardedAnyHandleMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter { count: u32 }
+    struct Name { name: &'static str }
+
+    #[test]
+    fn insert_get_remove() {
+        let map = ShardedAnyHandleMap::with_shards(2);
+        assert!(map.is_empty());
+        assert!(map.insert_value(Counter { count: 3 }).is_none());
+        assert!(map.insert_value(Name { name: "physics" }).is_none());
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get::<Counter>().unwrap().read().count, 3);
+        assert_eq!(map.get::<Name>().unwrap().read().name, "physics");
+        assert!(map.get::<u8>().is_none());
+
+        assert!(map.insert_value(Counter { count: 4 }).is_some());
+        assert_eq!(map.remove::<Counter>().unwrap().read().count, 4);
+        assert!(!map.contains::<Counter>());
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        let map = ShardedAnyHandleMap::with_hasher(std::collections::hash_map::RandomState::new());
+        map.insert_value(Counter { count: 1 });
+        assert_eq!(map.get::<Counter>().unwrap().read().count, 1);
+    }
+
+    #[test]
+    fn concurrent_registration() {
+        let map = ShardedAnyHandleMap::new();
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| map.get_or_insert_with(|| Counter { count: 0 }).write().count += 1);
+            }
+        });
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get::<Counter>().unwrap().read().count, 8);
+    }
+}
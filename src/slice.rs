@@ -0,0 +1,171 @@
+use crate::{Any, AnyHandle, AnyHandleReadGuard, AnyHandleWriteGuard, Contents, Erased};
+use std::any::TypeId;
+use std::ops::{Deref, DerefMut};
+
+// Unsized values are stored through a sized owner, such as `Box<str>` or
+// `String`, and the guards of a handle to the unsized type deref through it.
+
+fn view_str(value: &Erased) -> &str {
+    if let Some(text) = value.downcast_ref::<Box<str>>() {
+        text
+    } else {
+        value.downcast_ref::<String>().expect("str handle holds a different type")
+    }
+}
+
+fn view_str_mut(value: &mut Erased) -> &mut str {
+    if value.is::<Box<str>>() {
+        value.downcast_mut::<Box<str>>().unwrap()
+    } else {
+        value.downcast_mut::<String>().expect("str handle holds a different type")
+    }
+}
+
+fn view_slice<T: 'static>(value: &Erased) -> &[T] {
+    if let Some(items) = value.downcast_ref::<Box<[T]>>() {
+        items
+    } else {
+        value.downcast_ref::<Vec<T>>().expect("slice handle holds a different type")
+    }
+}
+
+fn view_slice_mut<T: 'static>(value: &mut Erased) -> &mut [T] {
+    if value.is::<Box<[T]>>() {
+        value.downcast_mut::<Box<[T]>>().unwrap()
+    } else {
+        value.downcast_mut::<Vec<T>>().expect("slice handle holds a different type")
+    }
+}
+
+impl AnyHandle<str> {
+    /// Initialize a handle to a string slice, which its guards deref to.
+    ///
+    /// The string is stored as a `Box<str>`, so the handle can be
+    /// [upcast](AnyHandle::upcast) and later recovered with
+    /// [downcast_str](AnyHandle::downcast_str) or `downcast::<Box<str>>()`.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let name = AnyHandle::from_boxed_str("player".into());
+    /// name.write().make_ascii_uppercase();
+    /// assert_eq!(&*name.read(), "PLAYER");
+    /// ```
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn from_boxed_str(value: Box<str>) -> Self {
+        AnyHandle::from_contents(Contents::Owned(Box::new(value)), TypeId::of::<Box<str>>(), Some(std::any::type_name::<Box<str>>())).cast_unchecked()
+    }
+}
+
+impl<T: Send + Sync + 'static> AnyHandle<[T]> {
+    /// Initialize a handle to a slice, which its guards deref to.
+    ///
+    /// The slice is stored as a `Box<[T]>`, so the handle can be
+    /// [upcast](AnyHandle::upcast) and later recovered with
+    /// [downcast_slice](AnyHandle::downcast_slice) or `downcast::<Box<[T]>>()`.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let bytes = AnyHandle::from_boxed_slice(vec![3u8, 1, 2].into_boxed_slice());
+    /// bytes.write().sort();
+    /// assert_eq!(&*bytes.read(), [1, 2, 3]);
+    /// ```
+    #[cfg_attr(feature = "track-origin", track_caller)]
+    pub fn from_boxed_slice(value: Box<[T]>) -> Self {
+        AnyHandle::from_contents(Contents::Owned(Box::new(value)), TypeId::of::<Box<[T]>>(), Some(std::any::type_name::<Box<[T]>>())).cast_unchecked()
+    }
+}
+
+impl AnyHandle<dyn Any> {
+    /// Downcast this handle to a view of the stored string as a `str`, if it
+    /// holds a `Box<str>` or a [String]. Otherwise, returns Err(self).
+    ///
+    /// The view shares the object with this handle. Writing through it can
+    /// change the string's contents, but not its length.
+    ///
+    /// ```
+    /// use any_handle::{AnyHandle, Any};
+    ///
+    /// let handle: AnyHandle<dyn Any> = AnyHandle::from_value(String::from("text")).upcast();
+    /// let text: AnyHandle<str> = handle.downcast_str().ok().unwrap();
+    /// assert_eq!(text.read().len(), 4);
+    /// ```
+    pub fn downcast_str(self) -> Result<AnyHandle<str>, Self> {
+        if self.is::<Box<str>>() || self.is::<String>() {
+            Ok(self.cast_unchecked())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Downcast this handle to a view of the stored items as a `[T]`, if it
+    /// holds a `Box<[T]>` or a [Vec]. Otherwise, returns Err(self).
+    ///
+    /// Like [downcast_str](Self::downcast_str), writing through the view can
+    /// change the items, but not how many there are.
+    pub fn downcast_slice<T: 'static>(self) -> Result<AnyHandle<[T]>, Self> {
+        if self.is::<Box<[T]>>() || self.is::<Vec<T>>() {
+            Ok(self.cast_unchecked())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+// Generate the Deref implementations for both guard types.
+macro_rules! impl_deref_unsized {
+    ($($Type:ident),+) => {$(
+        impl<'a> Deref for $Type<'a, str> {
+            type Target = str;
+
+            #[inline(always)]
+            fn deref(&self) -> &str {
+                view_str(self.0.value.get())
+            }
+        }
+
+        impl<'a, T: 'static> Deref for $Type<'a, [T]> {
+            type Target = [T];
+
+            #[inline(always)]
+            fn deref(&self) -> &[T] {
+                view_slice(self.0.value.get())
+            }
+        }
+    )+}
+}
+
+impl_deref_unsized!(AnyHandleReadGuard, AnyHandleWriteGuard);
+
+impl<'a> DerefMut for AnyHandleWriteGuard<'a, str> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut str {
+        view_str_mut(self.0.value.get_mut())
+    }
+}
+
+impl<'a, T: 'static> DerefMut for AnyHandleWriteGuard<'a, [T]> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut [T] {
+        view_slice_mut(self.0.value.get_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_share_the_object() {
+        let items = AnyHandle::from_value(vec![1u32, 2, 3]);
+        let view = items.clone().upcast().downcast_slice::<u32>().ok().unwrap();
+        view.write()[0] = 10;
+        assert_eq!(*items.read(), [10, 2, 3]);
+        assert!(items.upcast().downcast_slice::<u8>().is_err());
+
+        let boxed: AnyHandle<dyn Any> = AnyHandle::from_boxed_str("hello".into()).upcast();
+        assert_eq!(&**boxed.clone().downcast::<Box<str>>().ok().unwrap().read(), "hello");
+        assert_eq!(&*boxed.downcast_str().ok().unwrap().read(), "hello");
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{AnyHandle, Shared, Slot};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LockResult, RwLockReadGuard, RwLockWriteGuard, TryLockError, TryLockResult};
+use std::time::{Duration, Instant};
+
+/// Lock statistics for one object, shared by all of its handles, as returned
+/// by [contention_stats](AnyHandle::contention_stats). Requires the
+/// `contention-stats` feature.
+///
+/// Every lock taken through a handle is counted, including the brief ones
+/// taken by methods such as [version](AnyHandle::version).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContentionStats {
+    /// The number of read locks acquired.
+    pub reads: u64,
+    /// The number of write locks acquired.
+    pub writes: u64,
+    /// The number of attempts to lock which found the lock held, and had to
+    /// wait for it or, for non-blocking attempts, failed.
+    pub contended: u64,
+    /// The total time spent waiting for the lock.
+    pub wait_time: Duration,
+    /// The total time read guards were held for.
+    pub read_hold_time: Duration,
+    /// The total time write guards were held for.
+    pub write_hold_time: Duration,
+}
+
+impl std::ops::AddAssign for ContentionStats {
+    /// Accumulate the statistics of another object, such as when totalling
+    /// those of a whole store.
+    fn add_assign(&mut self, other: Self) {
+        self.reads += other.reads;
+        self.writes += other.writes;
+        self.contended += other.contended;
+        self.wait_time += other.wait_time;
+        self.read_hold_time += other.read_hold_time;
+        self.write_hold_time += other.write_hold_time;
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Counters {
+    reads: AtomicU64,
+    writes: AtomicU64,
+    contended: AtomicU64,
+    wait_nanos: AtomicU64,
+    read_hold_nanos: AtomicU64,
+    write_hold_nanos: AtomicU64,
+}
+
+fn add(counter: &AtomicU64, amount: u64) {
+    counter.fetch_add(amount, Ordering::Relaxed);
+}
+
+fn add_since(counter: &AtomicU64, start: Instant) {
+    add(counter, start.elapsed().as_nanos().try_into().unwrap_or(u64::MAX));
+}
+
+impl Counters {
+    /// Record the end of a read which began at `start`.
+    pub(crate) fn release_read(&self, start: Instant) {
+        add_since(&self.read_hold_nanos, start);
+    }
+
+    /// Record the end of a write which began at `start`.
+    pub(crate) fn release_write(&self, start: Instant) {
+        add_since(&self.write_hold_nanos, start);
+    }
+}
+
+// These shadow the lock's own methods, so every lock taken through a handle
+// is counted.
+impl Shared {
+    pub(crate) fn read(&self) -> LockResult<RwLockReadGuard<'_, Slot>> {
+        add(&self.stats.reads, 1);
+        match self.lock.try_read() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(poisoned)) => Err(poisoned),
+            Err(TryLockError::WouldBlock) => {
+                add(&self.stats.contended, 1);
+                let start = Instant::now();
+                let guard = self.lock.read();
+                add_since(&self.stats.wait_nanos, start);
+                guard
+            }
+        }
+    }
+
+    pub(crate) fn write(&self) -> LockResult<RwLockWriteGuard<'_, Slot>> {
+        add(&self.stats.writes, 1);
+        let mut guard = match self.lock.try_write() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(poisoned)) => Err(poisoned),
+            Err(TryLockError::WouldBlock) => {
+                add(&self.stats.contended, 1);
+                let start = Instant::now();
+                let guard = self.lock.write();
+                add_since(&self.stats.wait_nanos, start);
+                guard
+            }
+        };
+        if let Ok(guard) = &mut guard {
+            guard.write_acquired = Some(Instant::now());
+        }
+        guard
+    }
+
+    pub(crate) fn try_read(&self) -> TryLockResult<RwLockReadGuard<'_, Slot>> {
+        let guard = self.lock.try_read();
+        match &guard {
+            Err(TryLockError::WouldBlock) => add(&self.stats.contended, 1),
+            _ => add(&self.stats.reads, 1),
+        }
+        guard
+    }
+
+    pub(crate) fn try_write(&self) -> TryLockResult<RwLockWriteGuard<'_, Slot>> {
+        let mut guard = self.lock.try_write();
+        match &mut guard {
+            Err(TryLockError::WouldBlock) => add(&self.stats.contended, 1),
+            Ok(guard) => {
+                add(&self.stats.writes, 1);
+                guard.write_acquired = Some(Instant::now());
+            }
+            Err(TryLockError::Poisoned(_)) => add(&self.stats.writes, 1),
+        }
+        guard
+    }
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Get the lock statistics of this object, gathered from all of its
+    /// handles since it was created. Requires the `contention-stats` feature.
+    ///
+    /// This never takes the lock, so it can be used to find out which object
+    /// a stalled thread is waiting on.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let handle = AnyHandle::from_value(0u32);
+    /// *handle.write() += 1;
+    /// let _guard = handle.read();
+    /// assert!(handle.try_write().is_err());
+    ///
+    /// let stats = handle.contention_stats();
+    /// assert_eq!((stats.reads, stats.writes, stats.contended), (1, 1, 1));
+    /// ```
+    pub fn contention_stats(&self) -> ContentionStats {
+        let stats = &self.0.stats;
+        ContentionStats {
+            reads: stats.reads.load(Ordering::Relaxed),
+            writes: stats.writes.load(Ordering::Relaxed),
+            contended: stats.contended.load(Ordering::Relaxed),
+            wait_time: Duration::from_nanos(stats.wait_nanos.load(Ordering::Relaxed)),
+            read_hold_time: Duration::from_nanos(stats.read_hold_nanos.load(Ordering::Relaxed)),
+            write_hold_time: Duration::from_nanos(stats.write_hold_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_and_holds_are_timed() {
+        let handle = AnyHandle::from_value(0u32);
+        let guard = handle.write();
+        std::thread::scope(|scope| {
+            let reader = scope.spawn(|| *handle.read());
+            while handle.contention_stats().contended == 0 {
+                std::thread::yield_now();
+            }
+            std::thread::sleep(Duration::from_millis(5));
+            drop(guard);
+            assert_eq!(reader.join().unwrap(), 0);
+        });
+
+        let stats = handle.contention_stats();
+        assert_eq!((stats.reads, stats.writes, stats.contended), (1, 1, 1));
+        assert!(stats.wait_time >= Duration::from_millis(5));
+        assert!(stats.write_hold_time >= Duration::from_millis(5));
+
+        let guard = handle.read();
+        std::thread::sleep(Duration::from_millis(5));
+        drop(guard);
+        let stats = handle.contention_stats();
+        assert!(stats.read_hold_time >= Duration::from_millis(5));
+        assert_eq!(handle.upcast().contention_stats(), stats);
+    }
+}
@@ -0,0 +1,877 @@
+use crate::{AccessError, Any, AnyHandle, AnyHandleReadGuard, BuildTypeIdHasher, QuotaExceeded};
+use crate::memory::{self, PressureLevel};
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// A small, copyable reference to a handle in a [HandleStore].
+///
+/// An id pairs a slot index with the generation of the slot when the handle
+/// was inserted. Once the handle is removed, the slot's generation moves on,
+/// so stale ids resolve to nothing rather than to whatever replaced it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandleId {
+    index: u32,
+    generation: u32,
+}
+
+impl HandleId {
+    /// Get the slot index of this id.
+    #[inline(always)]
+    pub fn index(self) -> u32 {
+        self.index
+    }
+
+    /// Get the generation of this id.
+    #[inline(always)]
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// Pack this id into a single integer, such as to persist it in any
+    /// format. Ids read back with [from_bits](Self::from_bits) are meaningful
+    /// to the store they came from, or through the [IdRemap] returned by
+    /// [load](HandleStore::load).
+    #[inline(always)]
+    pub fn to_bits(self) -> u64 {
+        (u64::from(self.generation) << 32) | u64::from(self.index)
+    }
+
+    /// Unpack an id packed by [to_bits](Self::to_bits).
+    #[inline(always)]
+    pub fn from_bits(bits: u64) -> Self {
+        Self { index: bits as u32, generation: (bits >> 32) as u32 }
+    }
+}
+
+impl fmt::Debug for HandleId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HandleId({}v{})", self.index, self.generation)
+    }
+}
+
+/// Statistics about the handles of one type in a [HandleStore].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TypeStats {
+    /// The name of the type, if known (see [type_name](AnyHandle::type_name)).
+    pub name: Option<&'static str>,
+    /// The number of handles to values of the type.
+    pub count: usize,
+    /// The memory held by those values (see [memory_usage](AnyHandle::memory_usage)).
+    pub bytes: usize,
+}
+
+/// Statistics about a [HandleStore], as returned by [stats](HandleStore::stats).
+#[derive(Clone, Debug, Default)]
+pub struct StoreStats {
+    /// The number of handles in the store.
+    pub len: usize,
+    /// The memory held by all stored values.
+    pub bytes: usize,
+    /// Statistics for each stored type, keyed by its [TypeId].
+    pub types: HashMap<TypeId, TypeStats, BuildTypeIdHasher>,
+    /// The number of slots in the store's index, including free ones.
+    pub slots: usize,
+    /// The number of free slots, waiting to be reused.
+    pub free_slots: usize,
+    /// The lock statistics of every stored value, added together. Requires
+    /// the `contention-stats` feature.
+    #[cfg(feature = "contention-stats")]
+    pub locks: crate::ContentionStats,
+}
+
+impl StoreStats {
+    /// Get the statistics for values of type `T`, which are all zero if the
+    /// store holds none.
+    pub fn of<T: 'static>(&self) -> TypeStats {
+        self.types.get(&TypeId::of::<T>()).copied().unwrap_or_default()
+    }
+}
+
+/// A limit on the handles of one type in a [HandleStore], set with
+/// [set_quota](HandleStore::set_quota). Limits left as [None] are not
+/// enforced, so the default quota allows anything.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Quota {
+    /// The most handles of the type the store may hold.
+    pub max_count: Option<usize>,
+    /// The most memory the values of those handles may hold, as measured by
+    /// [memory_usage](AnyHandle::memory_usage) when each one is inserted.
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Clone, Copy)]
+struct Limit {
+    quota: Quota,
+    count: usize,
+    bytes: usize,
+}
+
+/// The ids of the handles moved by [compact](HandleStore::compact), or
+/// inserted by [load](HandleStore::load), mapping each old id to the new one.
+#[derive(Clone, Debug, Default)]
+pub struct IdRemap {
+    moved: HashMap<HandleId, HandleId>,
+}
+
+impl IdRemap {
+    /// Get the new id of the handle that `id` referred to, or `id` itself if
+    /// that handle was not moved.
+    pub fn remap(&self, id: HandleId) -> HandleId {
+        self.get(id).unwrap_or(id)
+    }
+
+    /// Get the new id of the handle that `id` referred to, or [None] if it
+    /// was not moved.
+    pub fn get(&self, id: HandleId) -> Option<HandleId> {
+        self.moved.get(&id).copied()
+    }
+
+    /// Iterate over the old and new ids of every moved handle.
+    pub fn iter(&self) -> impl Iterator<Item = (HandleId, HandleId)> + '_ {
+        self.moved.iter().map(|(&old, &new)| (old, new))
+    }
+
+    /// Get the number of moved handles.
+    pub fn len(&self) -> usize {
+        self.moved.len()
+    }
+
+    /// Check whether no handles were moved.
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty()
+    }
+}
+
+type RemapFn = Box<dyn Fn(&AnyHandle<dyn Any>, &IdRemap) + Send + Sync>;
+
+static REMAPS: RwLock<HashMap<TypeId, RemapFn, BuildTypeIdHasher>> = RwLock::new(HashMap::with_hasher(BuildTypeIdHasher::new()));
+
+/// Register `remap` as updating the [HandleId]s held inside a value of type
+/// `T`, so that [compact](HandleStore::compact) keeps them pointing at the
+/// same handles after moving them, and [load](HandleStore::load) points them
+/// at the handles loaded in place of the ones they were saved with.
+///
+/// ```
+/// use any_handle::{register_id_remap, HandleId, HandleStore};
+///
+/// struct Node { next: Option<HandleId> }
+///
+/// register_id_remap::<Node>(|node, ids| node.next = node.next.map(|id| ids.remap(id)));
+///
+/// let mut store = HandleStore::new();
+/// let gap = store.insert_value(());
+/// let tail = store.insert_value(Node { next: None });
+/// let head = store.insert_value(Node { next: Some(tail) });
+/// store.remove(gap);
+///
+/// let ids = store.compact();
+/// let head = store.get::<Node>(ids.remap(head)).unwrap();
+/// assert_eq!(head.read().next, Some(ids.remap(tail)));
+/// ```
+pub fn register_id_remap<T: Any + Send + Sync>(remap: fn(&mut T, &IdRemap)) {
+    let remap: RemapFn = Box::new(move |handle, ids| match handle.write_checked() {
+        Ok(guard) => remap(&mut guard.downcast::<T>().ok().expect("id remap invoked on the wrong type"), ids),
+        Err(AccessError::Revoked) => {}
+        Err(error) => panic!("{error}"),
+    });
+    REMAPS.write().unwrap().insert(TypeId::of::<T>(), remap);
+}
+
+#[derive(Clone)]
+struct Entry {
+    generation: u32,
+    handle: Option<AnyHandle<dyn Any>>,
+    // The type the handle was counted under, and the bytes charged to that
+    // type's quota, if it has one.
+    type_id: TypeId,
+    bytes: usize,
+}
+
+/// Pass `ids` to the registered remap hook of each handle's type.
+fn remap_ids<'a>(handles: impl Iterator<Item = &'a AnyHandle<dyn Any>>, ids: &IdRemap) {
+    let remaps = REMAPS.read().unwrap();
+    for handle in handles {
+        if let Some(remap) = remaps.get(&handle.type_id()) {
+            remap(handle, ids);
+        }
+    }
+}
+
+/// A store of handles addressed by generational [HandleId]s, for passing
+/// around plain ids instead of cloned handles, as in entity systems and
+/// asset managers.
+///
+/// Slots freed by [remove](Self::remove) are reused, each time under a new
+/// generation, so ids stay small and stale ones are detected.
+///
+/// # Example
+/// ```
+/// use any_handle::HandleStore;
+///
+/// struct Texture { width: u32 }
+///
+/// let mut store = HandleStore::new();
+/// let id = store.insert_value(Texture { width: 256 });
+/// assert_eq!(store.get::<Texture>(id).unwrap().read().width, 256);
+/// assert!(store.get::<u8>(id).is_none());
+///
+/// store.remove(id);
+/// let reused = store.insert_value(Texture { width: 512 });
+/// assert_eq!(reused.index(), id.index());
+/// assert!(store.get::<Texture>(id).is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct HandleStore {
+    entries: Vec<Entry>,
+    free: Vec<u32>,
+    len: usize,
+    // The generation new slots start at, past that of any slot truncated by
+    // compaction, so that ids into the old slot stay stale.
+    min_generation: u32,
+    limits: HashMap<TypeId, Limit, BuildTypeIdHasher>,
+}
+
+impl HandleStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a handle, returning the id it can be looked up by.
+    ///
+    /// # Panics
+    /// Panics if the store already holds `u32::MAX` slots, or if inserting
+    /// the handle would exceed the [quota](Self::set_quota) for its type; see
+    /// [try_insert](Self::try_insert) for a fallible version.
+    pub fn insert(&mut self, handle: AnyHandle<dyn Any>) -> HandleId {
+        self.try_insert(handle).unwrap()
+    }
+
+    /// Insert a handle like [insert](Self::insert), or give it back in a
+    /// [QuotaExceeded] error if that would exceed the quota for its type.
+    ///
+    /// Nothing is evicted to make room: the caller can remove handles of its
+    /// choosing and try again.
+    ///
+    /// # Panics
+    /// Panics if the store already holds `u32::MAX` slots.
+    pub fn try_insert(&mut self, handle: AnyHandle<dyn Any>) -> Result<HandleId, QuotaExceeded> {
+        let type_id = handle.type_id();
+        let mut bytes = 0;
+        if let Some(limit) = self.limits.get_mut(&type_id) {
+            let quota = limit.quota;
+            if quota.max_bytes.is_some() {
+                bytes = handle.memory_usage();
+            }
+            if quota.max_count.is_some_and(|max| limit.count >= max) || quota.max_bytes.is_some_and(|max| limit.bytes + bytes > max) {
+                return Err(QuotaExceeded { handle, quota });
+            }
+            limit.count += 1;
+            limit.bytes += bytes;
+        }
+
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let entry = &mut self.entries[index as usize];
+            *entry = Entry { generation: entry.generation, handle: Some(handle), type_id, bytes };
+            return Ok(HandleId { index, generation: entry.generation });
+        }
+
+        let index = u32::try_from(self.entries.len()).ok().filter(|&index| index < u32::MAX);
+        let index = index.expect("a HandleStore can hold at most u32::MAX slots");
+        let generation = self.min_generation;
+        self.entries.push(Entry { generation, handle: Some(handle), type_id, bytes });
+        Ok(HandleId { index, generation })
+    }
+
+    /// Wrap `value` in a new handle and insert it, returning its id.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [insert](Self::insert).
+    pub fn insert_value<T: Any + Send + Sync>(&mut self, value: T) -> HandleId {
+        self.insert(AnyHandle::from_value(value).upcast())
+    }
+
+    /// Wrap `value` in a new handle and insert it like [try_insert](Self::try_insert).
+    pub fn try_insert_value<T: Any + Send + Sync>(&mut self, value: T) -> Result<HandleId, QuotaExceeded> {
+        self.try_insert(AnyHandle::from_value(value).upcast())
+    }
+
+    /// Limit the handles of type `T` this store may hold, so that no one
+    /// kind of value, such as one plugin's, can take over a shared store.
+    /// Setting [Quota::default()] lifts the limits.
+    ///
+    /// The quota applies to later insertions only; handles already in the
+    /// store are counted against it but never removed. When measuring bytes,
+    /// the read lock of each existing `T` is taken, and each value is
+    /// measured once, so growth after insertion is not accounted for.
+    ///
+    /// ```
+    /// use any_handle::{HandleStore, Quota};
+    ///
+    /// let mut store = HandleStore::new();
+    /// store.set_quota::<String>(Quota { max_count: Some(1), max_bytes: None });
+    /// let first = store.insert_value(String::from("a"));
+    ///
+    /// let rejected = store.try_insert_value(String::from("b")).unwrap_err();
+    /// store.remove(first);
+    /// store.try_insert(rejected.into_handle()).unwrap();
+    /// ```
+    pub fn set_quota<T: 'static>(&mut self, quota: Quota) {
+        let type_id = TypeId::of::<T>();
+        let mut limit = Limit { quota, count: 0, bytes: 0 };
+        for entry in &mut self.entries {
+            let Some(handle) = entry.handle.as_ref().filter(|_| entry.type_id == type_id) else {
+                continue;
+            };
+            entry.bytes = if quota.max_bytes.is_some() { handle.memory_usage() } else { 0 };
+            limit.count += 1;
+            limit.bytes += entry.bytes;
+        }
+        self.limits.insert(type_id, limit);
+    }
+
+    /// Get the quota set for type `T`, which is [Quota::default()] if none
+    /// was set.
+    pub fn quota<T: 'static>(&self) -> Quota {
+        self.limits.get(&TypeId::of::<T>()).map(|limit| limit.quota).unwrap_or_default()
+    }
+
+    fn entry(&self, id: HandleId) -> Option<&AnyHandle<dyn Any>> {
+        let entry = self.entries.get(id.index as usize)?;
+        entry.handle.as_ref().filter(|_| entry.generation == id.generation)
+    }
+
+    /// Get the handle for `id`, or [None] if it has been removed.
+    pub fn get_any(&self, id: HandleId) -> Option<AnyHandle<dyn Any>> {
+        self.entry(id).cloned()
+    }
+
+    /// Get a typed handle for `id`, or [None] if it has been removed or does
+    /// not hold a `T`.
+    pub fn get<T: 'static>(&self, id: HandleId) -> Option<AnyHandle<T>> {
+        let handle = self.entry(id).filter(|handle| handle.is::<T>())?;
+        Some(handle.clone().cast_unchecked())
+    }
+
+    /// Get a typed handle for `id` that no other handle or store shares, so
+    /// that writes through it are seen by this store alone, first replacing
+    /// the stored handle with a copy of the value if it is shared. This is
+    /// how a value that a [fork](Self::fork) shares by handle is modified
+    /// without affecting the original.
+    ///
+    /// Like [make_mut](AnyHandle::make_mut), the value is also copied if it
+    /// is [read-only](AnyHandle::is_read_only), or if other handles to it are
+    /// held outside any store; those keep the value they had.
+    ///
+    /// Returns [None] if `id` has been removed or does not hold a `T`, or if
+    /// the value must be copied but its type was not registered with
+    /// [register_clone](crate::register_clone).
+    pub fn make_unique<T: 'static>(&mut self, id: HandleId) -> Option<AnyHandle<T>> {
+        let entry = self.entries.get_mut(id.index as usize).filter(|entry| entry.generation == id.generation)?;
+        let handle = entry.handle.as_mut().filter(|handle| handle.is::<T>())?;
+        if Arc::get_mut(&mut handle.0).is_none() || handle.is_read_only() {
+            *handle = handle.deep_clone()?;
+        }
+        Some(handle.clone().cast_unchecked())
+    }
+
+    /// Check whether `id` still refers to a handle in this store.
+    pub fn contains(&self, id: HandleId) -> bool {
+        self.entry(id).is_some()
+    }
+
+    /// Remove the handle for `id` and return it, invalidating `id` and any
+    /// copies of it. Returns [None] if it was already removed.
+    pub fn remove(&mut self, id: HandleId) -> Option<AnyHandle<dyn Any>> {
+        let entry = self.entries.get_mut(id.index as usize)?;
+        if entry.generation != id.generation {
+            return None;
+        }
+        let handle = entry.handle.take()?;
+        self.len -= 1;
+        if let Some(limit) = self.limits.get_mut(&entry.type_id) {
+            limit.count -= 1;
+            limit.bytes -= entry.bytes;
+        }
+
+        // A slot whose generation is exhausted is retired rather than risk
+        // handing out an id that collides with a stale one.
+        if let Some(generation) = entry.generation.checked_add(1) {
+            entry.generation = generation;
+            self.free.push(id.index);
+        }
+        Some(handle)
+    }
+
+    /// Iterate over the ids and handles in this store, in slot order.
+    pub fn iter(&self) -> impl Iterator<Item = (HandleId, &AnyHandle<dyn Any>)> {
+        self.entries.iter().zip(0..).filter_map(|(entry, index)| {
+            let id = HandleId { index, generation: entry.generation };
+            entry.handle.as_ref().map(|handle| (id, handle))
+        })
+    }
+
+    /// Iterate over the ids of the handles holding a `T` and read guards for
+    /// their values, in slot order.
+    ///
+    /// Each entry is locked only once the iterator reaches it, so a traversal
+    /// of a large store holds one lock at a time if each guard is dropped
+    /// before advancing. Entries revoked in the meantime are skipped.
+    ///
+    /// # Panics
+    /// Panics on reaching a [poisoned](AnyHandle::is_poisoned) entry, like
+    /// [read](AnyHandle::read).
+    ///
+    /// ```
+    /// use any_handle::HandleStore;
+    ///
+    /// let mut store = HandleStore::new();
+    /// store.insert_value(3u32);
+    /// store.insert_value("skipped");
+    /// store.insert_value(4u32);
+    ///
+    /// let total: u32 = store.iter_guards_of::<u32>().map(|(_, guard)| *guard).sum();
+    /// assert_eq!(total, 7);
+    /// ```
+    pub fn iter_guards_of<T: 'static>(&self) -> impl Iterator<Item = (HandleId, AnyHandleReadGuard<'_, T>)> {
+        self.iter().filter(|(_, handle)| handle.is::<T>()).filter_map(|(id, handle)| match handle.read_checked() {
+            Ok(guard) => Some((id, guard.downcast().ok()?)),
+            Err(AccessError::Revoked) => None,
+            Err(error) => panic!("{error}"),
+        })
+    }
+
+    /// Get the number of handles in this store.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether the store holds no handles.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gather statistics about the store and the values in it, for capacity
+    /// planning and regression tests.
+    ///
+    /// Each value's read lock is taken in turn to measure it, so this waits
+    /// for writers, and deadlocks if the current thread holds a write guard
+    /// for a stored value.
+    ///
+    /// ```
+    /// use any_handle::HandleStore;
+    ///
+    /// let mut store = HandleStore::new();
+    /// store.insert_value(1u32);
+    /// let id = store.insert_value(2u32);
+    /// store.insert_value(3u64);
+    /// store.remove(id);
+    ///
+    /// let stats = store.stats();
+    /// assert_eq!((stats.len, stats.slots, stats.free_slots), (2, 3, 1));
+    /// assert_eq!((stats.of::<u32>().count, stats.of::<u32>().bytes), (1, 4));
+    /// assert_eq!(stats.bytes, 12);
+    /// ```
+    pub fn stats(&self) -> StoreStats {
+        let mut stats = StoreStats { len: self.len, slots: self.entries.len(), free_slots: self.free.len(), ..StoreStats::default() };
+        for (_, handle) in self.iter() {
+            let bytes = handle.memory_usage();
+            let types = stats.types.entry(handle.type_id()).or_default();
+            types.name = types.name.or_else(|| handle.type_name());
+            types.count += 1;
+            types.bytes += bytes;
+            stats.bytes += bytes;
+            #[cfg(feature = "contention-stats")]
+            {
+                stats.locks += handle.contention_stats();
+            }
+        }
+        stats
+    }
+
+    /// Move handles from the end of the store into free slots nearer the
+    /// start, then release the empty slots left at the end, so that a store
+    /// that has seen heavy churn is dense again and quick to iterate.
+    ///
+    /// Returns the new ids of the moved handles; their old ids become stale,
+    /// and every other id is unaffected. Each value whose type was registered
+    /// with [register_id_remap] is then passed the remapping, under its write
+    /// lock, to update the ids it holds.
+    ///
+    /// # Panics
+    /// Panics if a registered value is [poisoned](AnyHandle::is_poisoned), and
+    /// deadlocks if the current thread holds a guard for one.
+    pub fn compact(&mut self) -> IdRemap {
+        let mut free = vec![false; self.entries.len()];
+        for &index in &self.free {
+            free[index as usize] = true;
+        }
+
+        let mut ids = IdRemap::default();
+        let (mut low, mut high) = (0, self.entries.len());
+        loop {
+            while low < high && !free[low] {
+                low += 1;
+            }
+            while low < high && self.entries[high - 1].handle.is_none() {
+                high -= 1;
+            }
+            if low == high {
+                break;
+            }
+            high -= 1;
+
+            let source = &mut self.entries[high];
+            let old = HandleId { index: high as u32, generation: source.generation };
+            let (handle, type_id, bytes) = (source.handle.take(), source.type_id, source.bytes);
+            // As in remove, a slot whose generation is exhausted is retired.
+            match source.generation.checked_add(1) {
+                Some(generation) => {
+                    source.generation = generation;
+                    free[high] = true;
+                }
+                None => free[high] = false,
+            }
+
+            let target = &mut self.entries[low];
+            (target.handle, target.type_id, target.bytes) = (handle, type_id, bytes);
+            free[low] = false;
+            ids.moved.insert(old, HandleId { index: low as u32, generation: target.generation });
+        }
+
+        while let Some(entry) = self.entries.last().filter(|entry| entry.handle.is_none() && free[self.entries.len() - 1]) {
+            self.min_generation = self.min_generation.max(entry.generation);
+            self.entries.pop();
+        }
+        self.free = (0..self.entries.len() as u32).rev().filter(|&index| free[index as usize]).collect();
+
+        if !ids.is_empty() {
+            remap_ids(self.iter().map(|(_, handle)| handle), &ids);
+        }
+        ids
+    }
+
+    /// Insert handles loaded from persistent storage, each paired with the
+    /// id it had in the store it was saved from, such as one read back with
+    /// [HandleId::from_bits].
+    ///
+    /// Returns the new id of each loaded handle, keyed by its saved id. Each
+    /// loaded value whose type was registered with [register_id_remap] is
+    /// then passed the remapping, under its write lock, so that the ids it
+    /// holds refer to the loaded handles. Handles already in the store are
+    /// left alone.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [insert](Self::insert), or if a
+    /// registered value is [poisoned](AnyHandle::is_poisoned).
+    ///
+    /// ```
+    /// use any_handle::{register_id_remap, AnyHandle, HandleId, HandleStore};
+    ///
+    /// struct Node { next: Option<HandleId> }
+    ///
+    /// register_id_remap::<Node>(|node, ids| node.next = node.next.map(|id| ids.remap(id)));
+    ///
+    /// // Saved as (id, next) pairs packed with `to_bits`.
+    /// let saved = [(7, None), ((3 << 32) | 2, Some(7))];
+    ///
+    /// let mut store = HandleStore::new();
+    /// store.insert_value("already here");
+    /// let ids = store.load(saved.iter().map(|&(id, next)| {
+    ///     let node = Node { next: next.map(HandleId::from_bits) };
+    ///     (HandleId::from_bits(id), AnyHandle::from_value(node).upcast())
+    /// }));
+    ///
+    /// let head = store.get::<Node>(ids.remap(HandleId::from_bits((3 << 32) | 2))).unwrap();
+    /// assert_eq!(head.read().next, Some(ids.remap(HandleId::from_bits(7))));
+    /// assert!(store.get::<Node>(head.read().next.unwrap()).is_some());
+    /// ```
+    pub fn load(&mut self, handles: impl IntoIterator<Item = (HandleId, AnyHandle<dyn Any>)>) -> IdRemap {
+        let mut ids = IdRemap::default();
+        let mut loaded = Vec::new();
+        for (saved, handle) in handles {
+            ids.moved.insert(saved, self.insert(handle.clone()));
+            loaded.push(handle);
+        }
+        remap_ids(loaded.iter(), &ids);
+        ids
+    }
+
+    /// Run the purge hooks registered with
+    /// [on_memory_pressure](AnyHandle::on_memory_pressure) on the handles in
+    /// this store, and no others, such as when this store alone is over its
+    /// memory budget. Global hooks and those of other handles are not run;
+    /// see [memory::trim](crate::memory::trim) to run every hook.
+    ///
+    /// ```
+    /// use any_handle::memory::PressureLevel;
+    /// use any_handle::{AnyHandle, HandleStore};
+    ///
+    /// let cached = AnyHandle::from_value(vec![0u8; 64]);
+    /// cached.on_memory_pressure(|cache, _| cache.clear());
+    /// let elsewhere = AnyHandle::from_value(vec![0u8; 64]);
+    /// elsewhere.on_memory_pressure(|cache, _| cache.clear());
+    ///
+    /// let mut store = HandleStore::new();
+    /// store.insert(cached.clone().upcast());
+    /// store.trim(PressureLevel::Low);
+    /// assert!(cached.read().is_empty());
+    /// assert_eq!(elsewhere.read().len(), 64);
+    /// ```
+    pub fn trim(&self, level: PressureLevel) {
+        let objects: HashSet<usize> = self.iter().map(|(_, handle)| memory::object_address(&handle.0)).collect();
+        memory::trim_where(level, |object| object.is_some_and(|object| objects.contains(&object)));
+    }
+
+    /// Make a copy of this store that shares every value with it, such as to
+    /// try out changes on a branch of a simulation. Values are not copied up
+    /// front, so forking is cheap, and ids are valid in both stores.
+    ///
+    /// Each value stays shared until it is written through either store, when
+    /// the writer copies it with the function registered by
+    /// [register_clone](crate::register_clone), so the other store keeps the
+    /// value it had. Values which cannot be copied, because their type was
+    /// not registered or they are [lazy](AnyHandle::new_lazy) and have not
+    /// been produced yet, are shared by handle instead, so writes to them are
+    /// seen by both stores.
+    ///
+    /// Forking takes the write lock of each value in turn, so it waits for
+    /// any writer, and deadlocks if the current thread holds a guard for a
+    /// value in the store.
+    ///
+    /// ```
+    /// use any_handle::{register_clone, HandleStore};
+    ///
+    /// #[derive(Clone)]
+    /// struct Position(i32);
+    /// register_clone::<Position>();
+    ///
+    /// let mut world = HandleStore::new();
+    /// let id = world.insert_value(Position(0));
+    ///
+    /// let branch = world.fork();
+    /// branch.get::<Position>(id).unwrap().write().0 = 5;
+    /// assert_eq!(world.get::<Position>(id).unwrap().read().0, 0);
+    /// assert_eq!(branch.get::<Position>(id).unwrap().read().0, 5);
+    /// ```
+    pub fn fork(&self) -> HandleStore {
+        let mut fork = self.clone();
+        for entry in &mut fork.entries {
+            if let Some(handle) = &mut entry.handle {
+                if let Some(copy) = handle.fork() {
+                    *handle = copy;
+                }
+            }
+        }
+        fork
+    }
+
+    /// Remove every handle from the store, invalidating all of their ids.
+    pub fn clear(&mut self) {
+        let ids: Vec<HandleId> = self.iter().map(|(id, _)| id).collect();
+        for id in ids {
+            self.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Mesh { vertices: usize }
+
+    #[test]
+    fn stale_ids_are_detected() {
+        let mut store = HandleStore::new();
+        let first = store.insert_value(Mesh { vertices: 3 });
+        let second = store.insert_value(Mesh { vertices: 4 });
+        assert_eq!(store.len(), 2);
+
+        assert!(store.remove(first).is_some());
+        assert!(store.remove(first).is_none());
+        let third = store.insert_value(Mesh { vertices: 5 });
+        assert_eq!((third.index(), third.generation()), (first.index(), 1));
+
+        assert!(!store.contains(first));
+        assert_eq!(store.get::<Mesh>(third).unwrap().read().vertices, 5);
+        assert_eq!(store.iter().map(|(id, _)| id).collect::<Vec<_>>(), [third, second]);
+
+        store.clear();
+        assert!(store.is_empty());
+        assert!(!store.contains(second));
+    }
+
+    #[test]
+    fn exhausted_slots_are_retired() {
+        let mut store = HandleStore::new();
+        let id = store.insert_value(Mesh { vertices: 0 });
+        store.entries[0].generation = u32::MAX;
+        let id = HandleId { generation: u32::MAX, ..id };
+
+        store.remove(id);
+        assert_ne!(store.insert_value(Mesh { vertices: 1 }).index(), id.index());
+    }
+
+    #[test]
+    fn guards_are_taken_one_at_a_time() {
+        let mut store = HandleStore::new();
+        let first = store.insert_value(Mesh { vertices: 1 });
+        store.insert_value(0u8);
+        let second = store.insert_value(Mesh { vertices: 2 });
+
+        let mut guards = store.iter_guards_of::<Mesh>();
+        let (id, guard) = guards.next().unwrap();
+        assert_eq!((id, guard.vertices), (first, 1));
+        let other = store.get::<Mesh>(second).unwrap();
+        assert!(other.try_write().is_ok());
+        assert!(store.get::<Mesh>(first).unwrap().try_write().is_err());
+        drop(guard);
+
+        let (id, guard) = guards.next().unwrap();
+        assert_eq!((id, guard.vertices), (second, 2));
+        assert!(guards.next().is_none());
+    }
+
+    #[test]
+    fn compaction_fills_gaps_and_truncates() {
+        let mut store = HandleStore::new();
+        let ids: Vec<HandleId> = (0..6).map(|vertices| store.insert_value(Mesh { vertices })).collect();
+        for &id in &ids[..4] {
+            store.remove(id);
+        }
+
+        let remap = store.compact();
+        assert_eq!(remap.len(), 2);
+        assert_eq!(store.entries.len(), 2);
+        assert!(store.free.is_empty());
+        for &id in &ids[4..] {
+            let new = remap.remap(id);
+            assert!(new.index() < 2);
+            assert!(!store.contains(id));
+            assert_eq!(store.get::<Mesh>(new).unwrap().read().vertices, id.index() as usize);
+        }
+
+        let reinserted = store.insert_value(Mesh { vertices: 6 });
+        assert_eq!(reinserted.index(), 2);
+        assert!(!store.contains(ids[2]));
+        assert!(reinserted.generation() > ids[5].generation());
+    }
+
+    #[test]
+    fn compaction_keeps_retired_slots() {
+        let mut store = HandleStore::new();
+        let first = store.insert_value(Mesh { vertices: 0 });
+        let second = store.insert_value(Mesh { vertices: 1 });
+        store.remove(first);
+        store.entries[1].generation = u32::MAX;
+        let second = HandleId { generation: u32::MAX, ..second };
+
+        let remap = store.compact();
+        assert_eq!(remap.remap(second), HandleId { index: 0, generation: 1 });
+        assert_eq!(store.entries.len(), 2);
+        assert!(store.free.is_empty());
+        assert_eq!(store.insert_value(Mesh { vertices: 2 }).index(), 2);
+    }
+
+    #[test]
+    fn quotas_limit_insertion() {
+        let mut store = HandleStore::new();
+        store.insert_value(Mesh { vertices: 0 });
+        store.set_quota::<Mesh>(Quota { max_count: Some(2), max_bytes: None });
+        store.set_quota::<Vec<u8>>(Quota { max_count: None, max_bytes: Some(3 * std::mem::size_of::<Vec<u8>>()) });
+
+        let second = store.insert_value(Mesh { vertices: 1 });
+        let error = store.try_insert_value(Mesh { vertices: 2 }).unwrap_err();
+        assert_eq!(error.quota().max_count, Some(2));
+        assert!(error.into_handle().is::<Mesh>());
+        store.remove(second);
+        store.insert_value(Mesh { vertices: 3 });
+
+        for _ in 0..3 {
+            store.insert_value(Vec::<u8>::new());
+        }
+        assert!(store.try_insert_value(Vec::<u8>::new()).is_err());
+        assert!(store.try_insert_value(0u8).is_ok());
+
+        store.set_quota::<Mesh>(Quota::default());
+        store.insert_value(Mesh { vertices: 4 });
+        assert_eq!(store.quota::<Mesh>(), Quota::default());
+    }
+
+    #[test]
+    fn forks_copy_on_first_write() {
+        #[derive(Clone)]
+        struct Score { points: u32 }
+        crate::register_clone::<Score>();
+
+        let mut store = HandleStore::new();
+        let score = store.insert_value(Score { points: 1 });
+        let mesh = store.insert_value(Mesh { vertices: 3 });
+        let mut fork = store.fork();
+        assert!(fork.get_any(mesh).unwrap().ptr_eq(&store.get_any(mesh).unwrap()));
+
+        fork.get::<Score>(score).unwrap().write().points = 2;
+        assert_eq!(store.get::<Score>(score).unwrap().read().points, 1);
+        assert_eq!(fork.get::<Score>(score).unwrap().read().points, 2);
+        store.get::<Score>(score).unwrap().write().points = 3;
+        assert_eq!(fork.get::<Score>(score).unwrap().read().points, 2);
+        let address = Arc::as_ptr(&fork.get_any(score).unwrap().0);
+        fork.make_unique::<Score>(score).unwrap();
+        assert_eq!(Arc::as_ptr(&fork.get_any(score).unwrap().0), address);
+
+        assert!(fork.make_unique::<Mesh>(mesh).is_none());
+        assert!(fork.make_unique::<Score>(mesh).is_none());
+        drop(store);
+        assert!(fork.make_unique::<Mesh>(mesh).is_some());
+    }
+
+    #[test]
+    fn ids_round_trip_through_bits() {
+        let id = HandleId { index: 5, generation: u32::MAX };
+        assert_eq!(HandleId::from_bits(id.to_bits()), id);
+        assert_eq!(HandleId::from_bits(0), HandleId { index: 0, generation: 0 });
+    }
+
+    #[test]
+    fn loading_remaps_saved_ids() {
+        struct Link { to: HandleId }
+        register_id_remap::<Link>(|link, ids| link.to = ids.remap(link.to));
+
+        let mut saved = HandleStore::new();
+        let gap = saved.insert_value(());
+        saved.remove(gap);
+        let target = saved.insert_value(Mesh { vertices: 8 });
+        let link = saved.insert_value(Link { to: target });
+        let persisted: Vec<_> = saved.iter().map(|(id, handle)| (id.to_bits(), handle.clone())).collect();
+
+        let mut store = HandleStore::new();
+        let existing = store.insert_value(Link { to: target });
+        let ids = store.load(persisted.into_iter().map(|(id, handle)| (HandleId::from_bits(id), handle)));
+        assert_eq!(ids.len(), 2);
+        let link = store.get::<Link>(ids.remap(link)).unwrap();
+        assert_eq!(link.read().to, ids.remap(target));
+        assert_eq!(store.get::<Mesh>(link.read().to).unwrap().read().vertices, 8);
+        assert_eq!(store.get::<Link>(existing).unwrap().read().to, target);
+    }
+
+    #[test]
+    fn stats_count_types_and_heap_memory() {
+        crate::memory::register_size::<Vec<u16>>(|v| v.capacity() * 2);
+        let mut store = HandleStore::new();
+        store.insert_value(Vec::<u16>::with_capacity(10));
+        store.insert_value(Mesh { vertices: 1 });
+        store.insert_value(Mesh { vertices: 2 });
+
+        let stats = store.stats();
+        let vecs = stats.of::<Vec<u16>>();
+        assert_eq!((vecs.count, vecs.bytes), (1, std::mem::size_of::<Vec<u16>>() + 20));
+        assert_eq!(stats.of::<Mesh>().count, 2);
+        assert_eq!(stats.of::<Mesh>().name, Some(std::any::type_name::<Mesh>()));
+        assert_eq!(stats.of::<u8>(), TypeStats::default());
+        assert_eq!(stats.bytes, vecs.bytes + 2 * std::mem::size_of::<Mesh>());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{Any, AnyHandle, Erased};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+type Converter = Box<dyn Fn(&Erased) -> Box<Erased> + Send + Sync>;
+
+struct Slot {
+    current: RwLock<AnyHandle<dyn Any>>,
+    converters: RwLock<HashMap<(TypeId, TypeId), Converter>>,
+}
+
+/// An indirection over an [AnyHandle] whose underlying object can be
+/// replaced atomically, with every clone observing the replacement.
+///
+/// This suits plugin hot reloading: consumers hold a `SwappableAnyHandle`
+/// and [load](Self::load) the current implementation when they need it,
+/// while the host installs new ones with [swap](Self::swap). State may be
+/// carried over to the new type with a converter registered through
+/// [register_converter](Self::register_converter).
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, SwappableAnyHandle};
+///
+/// struct PluginV1 { calls: u32 }
+/// struct PluginV2 { calls: u64 }
+///
+/// let plugin = SwappableAnyHandle::new(AnyHandle::new(Box::new(PluginV1 { calls: 3 })));
+/// let consumer = plugin.clone();
+///
+/// plugin.register_converter(|old: &PluginV1| PluginV2 { calls: old.calls.into() });
+/// assert!(plugin.migrate_to::<PluginV2>().is_some());
+///
+/// assert_eq!(consumer.get::<PluginV2>().unwrap().read().calls, 3);
+/// ```
+#[derive(Clone)]
+pub struct SwappableAnyHandle(Arc<Slot>);
+
+impl SwappableAnyHandle {
+    /// Create a swappable handle that initially refers to `handle`.
+    pub fn new(handle: AnyHandle<dyn Any>) -> Self {
+        Self(Arc::new(Slot {
+            current: RwLock::new(handle),
+            converters: RwLock::default(),
+        }))
+    }
+
+    /// Get the handle currently installed.
+    pub fn load(&self) -> AnyHandle<dyn Any> {
+        self.0.current.read().unwrap().clone()
+    }
+
+    /// Get the handle currently installed, if it holds a `T`.
+    pub fn get<T: 'static>(&self) -> Option<AnyHandle<T>> {
+        self.load().downcast().ok()
+    }
+
+    /// Install `handle`, returning the one it replaces.
+    pub fn swap(&self, handle: AnyHandle<dyn Any>) -> AnyHandle<dyn Any> {
+        std::mem::replace(&mut *self.0.current.write().unwrap(), handle)
+    }
+
+    /// Wrap `value` in a new handle and install it, returning the handle it replaces.
+    pub fn swap_value<T: Any + Send + Sync>(&self, value: T) -> AnyHandle<dyn Any> {
+        self.swap(AnyHandle::from_value(value).upcast())
+    }
+
+    /// Register a function that migrates the state of an `A` into a new `B`,
+    /// for use by [migrate_to](Self::migrate_to). Replaces any converter
+    /// previously registered for the same pair of types.
+    pub fn register_converter<A, B>(&self, convert: impl Fn(&A) -> B + Send + Sync + 'static)
+    where
+        A: Any,
+        B: Any + Send + Sync,
+    {
+        let converter: Converter = Box::new(move |old| {
+            let old = old.downcast_ref::<A>().expect("converter invoked on the wrong type");
+            Box::new(convert(old))
+        });
+        let key = (TypeId::of::<A>(), TypeId::of::<B>());
+        self.0.converters.write().unwrap().insert(key, converter);
+    }
+
+    /// Replace the current object with a `B` built from it by a registered
+    /// converter, returning the handle that was replaced.
+    ///
+    /// The old object is read and the new one installed without releasing
+    /// the slot in between, so no concurrent swap can be lost. Returns [None],
+    /// leaving the current object in place, if no converter from its type to
+    /// `B` has been registered, or if the current object is
+    /// [poisoned](AnyHandle::is_poisoned).
+    pub fn migrate_to<B: Any + Send + Sync>(&self) -> Option<AnyHandle<dyn Any>> {
+        let mut current = self.0.current.write().unwrap();
+        let converters = self.0.converters.read().unwrap();
+        let converter = converters.get(&(current.stored_type_id(), TypeId::of::<B>()))?;
+        let migrated = converter(current.0.read().ok()?.value.get());
+        Some(std::mem::replace(&mut *current, AnyHandle::new(migrated)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Old { value: u8 }
+    struct New { value: u16 }
+
+    #[test]
+    fn swap_is_visible_to_clones() {
+        let swappable = SwappableAnyHandle::new(AnyHandle::new(Box::new(Old { value: 1 })));
+        let clone = swappable.clone();
+
+        let old = swappable.swap_value(New { value: 2 });
+        assert!(old.downcast::<Old>().is_ok());
+        assert!(clone.get::<Old>().is_none());
+        assert_eq!(clone.get::<New>().unwrap().read().value, 2);
+    }
+
+    #[test]
+    fn migration_requires_converter() {
+        let swappable = SwappableAnyHandle::new(AnyHandle::new(Box::new(Old { value: 7 })));
+        assert!(swappable.migrate_to::<New>().is_none());
+        assert!(swappable.get::<Old>().is_some());
+
+        swappable.register_converter(|old: &Old| New { value: old.value as u16 * 2 });
+        swappable.migrate_to::<New>().unwrap();
+        assert_eq!(swappable.get::<New>().unwrap().read().value, 14);
+    }
+}
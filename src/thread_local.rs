@@ -0,0 +1,129 @@
+use crate::{Any, AnyHandle, BuildTypeIdHasher, Erased};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::thread::ThreadId;
+
+type Factory = Arc<dyn Fn() -> Box<Erased> + Send + Sync>;
+type Instances = HashMap<TypeId, AnyHandle<dyn Any>, BuildTypeIdHasher>;
+
+/// A store in which each thread gets its own instance of every registered type.
+///
+/// Instances are created lazily, from the type's registered factory, the first
+/// time a thread asks for them. Every instance also remains visible to other
+/// threads through [for_each](Self::for_each), so per-thread scratch state can
+/// be aggregated when needed. Instances outlive the threads that created them
+/// until [clear](Self::clear) is called.
+///
+/// # Example
+/// ```
+/// use any_handle::ThreadLocalStore;
+///
+/// struct Hits(u32);
+///
+/// let store = ThreadLocalStore::new();
+/// store.register(|| Hits(0));
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| store.get::<Hits>().unwrap().write().0 += 1);
+///     }
+/// });
+///
+/// let mut total = 0;
+/// store.for_each(|_, hits: &any_handle::AnyHandle<Hits>| total += hits.read().0);
+/// assert_eq!(total, 4);
+/// ```
+#[derive(Default)]
+pub struct ThreadLocalStore {
+    factories: RwLock<HashMap<TypeId, Factory, BuildTypeIdHasher>>,
+    threads: RwLock<HashMap<ThreadId, Instances>>,
+}
+
+impl ThreadLocalStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `factory` to create each thread's instance of `T`.
+    /// Instances that already exist are left untouched.
+    pub fn register<T: Any + Send + Sync>(&self, factory: impl Fn() -> T + Send + Sync + 'static) {
+        let factory: Factory = Arc::new(move || Box::new(factory()));
+        self.factories.write().unwrap().insert(TypeId::of::<T>(), factory);
+    }
+
+    /// Get the current thread's instance of `T`, creating it if needed.
+    /// Returns [None] if no factory is registered for `T`.
+    pub fn get<T: 'static>(&self) -> Option<AnyHandle<T>> {
+        let key = TypeId::of::<T>();
+        let thread = std::thread::current().id();
+        if let Some(handle) = self.threads.read().unwrap().get(&thread).and_then(|instances| instances.get(&key)) {
+            return Some(handle.clone().cast_unchecked());
+        }
+
+        // Only this thread creates its own instances, so nothing can race us here.
+        let factory = self.factories.read().unwrap().get(&key)?.clone();
+        let handle = AnyHandle::new(factory());
+        let mut threads = self.threads.write().unwrap();
+        threads.entry(thread).or_default().insert(key, handle.clone());
+        Some(handle.cast_unchecked())
+    }
+
+    /// Call `visit` with every thread's instance of `T`.
+    ///
+    /// The store is locked while visiting, so `visit` must not create instances.
+    pub fn for_each<T: 'static>(&self, mut visit: impl FnMut(ThreadId, &AnyHandle<T>)) {
+        let key = TypeId::of::<T>();
+        for (thread, instances) in self.threads.read().unwrap().iter() {
+            if let Some(handle) = instances.get(&key) {
+                visit(*thread, &handle.clone().cast_unchecked());
+            }
+        }
+    }
+
+    /// Collect every thread's instance of `T`.
+    pub fn instances<T: 'static>(&self) -> Vec<AnyHandle<T>> {
+        let mut instances = Vec::new();
+        self.for_each(|_, handle: &AnyHandle<T>| instances.push(handle.clone()));
+        instances
+    }
+
+    /// Drop every instance created by the current thread.
+    pub fn clear_current_thread(&self) {
+        self.threads.write().unwrap().remove(&std::thread::current().id());
+    }
+
+    /// Drop every instance of every thread. Registered factories are kept.
+    pub fn clear(&self) {
+        self.threads.write().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scratch { buffer: Vec<u8> }
+
+    #[test]
+    fn each_thread_gets_its_own_instance() {
+        let store = ThreadLocalStore::new();
+        assert!(store.get::<Scratch>().is_none());
+        store.register(|| Scratch { buffer: Vec::new() });
+
+        store.get::<Scratch>().unwrap().write().buffer.push(1);
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                assert!(store.get::<Scratch>().unwrap().read().buffer.is_empty());
+                store.get::<Scratch>().unwrap().write().buffer.push(2);
+            });
+        });
+        assert_eq!(store.get::<Scratch>().unwrap().read().buffer, [1]);
+        assert_eq!(store.instances::<Scratch>().len(), 2);
+
+        store.clear_current_thread();
+        assert_eq!(store.instances::<Scratch>().len(), 1);
+        assert!(store.get::<Scratch>().unwrap().read().buffer.is_empty());
+    }
+}
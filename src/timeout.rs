@@ -0,0 +1,77 @@
+use crate::{AccessError, AnyHandle, AnyHandleReadGuard, AnyHandleWriteGuard};
+use std::sync::TryLockError;
+use std::time::{Duration, Instant};
+
+/// Attempts spent yielding the thread before sleeping between retries.
+const SPINS: u32 = 16;
+/// The longest sleep between two attempts.
+const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+/// Retry `try_lock` until it succeeds or `timeout` has elapsed.
+fn retry<G>(timeout: Duration, mut try_lock: impl FnMut() -> Result<G, TryLockError<G>>) -> Result<G, AccessError> {
+    let deadline = Instant::now() + timeout;
+    let mut sleep = Duration::from_micros(1);
+    for attempt in 0.. {
+        match try_lock() {
+            Ok(guard) => return Ok(guard),
+            Err(TryLockError::Poisoned(_)) => return Err(AccessError::Poisoned),
+            Err(TryLockError::WouldBlock) => {}
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        if attempt < SPINS {
+            std::thread::yield_now();
+        } else {
+            std::thread::sleep(sleep.min(deadline - now));
+            sleep = (sleep * 2).min(MAX_SLEEP);
+        }
+    }
+    Err(AccessError::TimedOut)
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Get a read guard, waiting at most `timeout` for any write guard to be
+    /// released, or [AccessError::TimedOut] if it is not.
+    pub fn read_timeout(&self, timeout: Duration) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        AnyHandleReadGuard::acquire(retry(timeout, || self.0.try_read())?)
+    }
+
+    /// Get a write guard, waiting at most `timeout` for every other guard to
+    /// be released, or [AccessError::TimedOut] if they are not.
+    pub fn write_timeout(&self, timeout: Duration) -> Result<AnyHandleWriteGuard<'_, T>, AccessError> {
+        AnyHandleWriteGuard::acquire(retry(timeout, || self.0.try_write())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn gives_up_after_the_deadline() {
+        let handle = AnyHandle::new(Box::new(0u32)).downcast::<u32>().ok().unwrap();
+        let writer = handle.clone();
+        let (locked, wait_locked) = mpsc::channel();
+        let (release, wait_release) = mpsc::channel::<()>();
+
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                let mut guard = writer.write();
+                *guard = 1;
+                locked.send(()).unwrap();
+                wait_release.recv().unwrap();
+            });
+            wait_locked.recv().unwrap();
+
+            let start = Instant::now();
+            assert_eq!(handle.read_timeout(Duration::from_millis(20)).err(), Some(AccessError::TimedOut));
+            assert!(start.elapsed() >= Duration::from_millis(20));
+            release.send(()).unwrap();
+        });
+
+        assert_eq!(*handle.write_timeout(Duration::from_secs(5)).unwrap(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{AccessError, Any, AnyHandle, Slot};
+use crate::watch::SlotReadGuard;
+use std::sync::Arc;
+
+/// A mutually consistent view of several handles at once.
+///
+/// Every handle is read-locked before the transaction is returned, and
+/// remains locked until it is dropped, so no write to any of them can land
+/// between observing one and another. Locks are acquired in a fixed global
+/// order, so concurrent transactions over overlapping handles cannot deadlock
+/// one another. Handles that appear more than once are only locked once.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, ReadTransaction};
+///
+/// let from = AnyHandle::new(Box::new(90i64));
+/// let to = AnyHandle::new(Box::new(10i64));
+///
+/// let view = ReadTransaction::new(&[&from, &to]);
+/// let total = view.get::<i64>(0).unwrap() + view.get::<i64>(1).unwrap();
+/// assert_eq!(total, 100);
+/// assert_eq!(view.version(1), 0);
+/// ```
+pub struct ReadTransaction<'a> {
+    guards: Vec<SlotReadGuard<'a>>,
+    positions: Vec<usize>,
+}
+
+impl<'a> ReadTransaction<'a> {
+    /// Read-lock every handle in `handles`, blocking until all are available.
+    ///
+    /// # Panics
+    /// Panics if any of the handles is [poisoned](AnyHandle::is_poisoned); see
+    /// [new_checked](Self::new_checked) for a fallible version.
+    pub fn new(handles: &[&'a AnyHandle<dyn Any>]) -> Self {
+        Self::new_checked(handles).unwrap()
+    }
+
+    /// Read-lock every handle like [new](Self::new), or fail with
+    /// [AccessError::Poisoned] if any of them is poisoned, releasing the
+    /// locks already taken.
+    pub fn new_checked(handles: &[&'a AnyHandle<dyn Any>]) -> Result<Self, AccessError> {
+        let address = |index: usize| Arc::as_ptr(&handles[index].0) as usize;
+        let mut order: Vec<usize> = (0..handles.len()).collect();
+        order.sort_by_key(|&index| address(index));
+
+        let mut guards = Vec::new();
+        let mut positions = vec![0; handles.len()];
+        for (rank, &index) in order.iter().enumerate() {
+            if rank == 0 || address(order[rank - 1]) != address(index) {
+                guards.push(SlotReadGuard::new(handles[index].0.read().map_err(|_| AccessError::Poisoned)?));
+            }
+            positions[index] = guards.len() - 1;
+        }
+        Ok(Self { guards, positions })
+    }
+
+    fn slot(&self, index: usize) -> &Slot {
+        &self.guards[self.positions[index]]
+    }
+
+    /// Get the object of the handle at `index`, if it is a `T`.
+    /// Revoked handles hold no object and always give [None].
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get<T: 'static>(&self, index: usize) -> Option<&T> {
+        self.slot(index).value.get().downcast_ref()
+    }
+
+    /// Get the version observed for the handle at `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn version(&self, index: usize) -> u64 {
+        self.slot(index).version
+    }
+
+    /// Count the handles in the transaction, including repeats.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Check whether the transaction covers no handles.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn writers_wait_for_the_transaction() {
+        let a = AnyHandle::new(Box::new(1u32));
+        let b = AnyHandle::new(Box::new("b"));
+        let (done, finished) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            let view = ReadTransaction::new(&[&b, &a, &b]);
+            assert_eq!(view.len(), 3);
+            assert_eq!(view.get::<&str>(2), Some(&"b"));
+            assert_eq!(view.get::<u32>(0), None);
+
+            let writer = a.clone();
+            scope.spawn(move || {
+                *writer.downcast::<u32>().ok().unwrap().write() = 2;
+                done.send(()).unwrap();
+            });
+            assert!(finished.recv_timeout(Duration::from_millis(50)).is_err());
+            assert_eq!(view.get::<u32>(1), Some(&1));
+        });
+
+        finished.recv().unwrap();
+        assert_eq!(ReadTransaction::new(&[&a]).version(0), 1);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{Any, AnyHandle};
+use std::any::TypeId;
+
+/// Dispatch on the type stored in an erased handle, produced by
+/// [visit](AnyHandle::visit).
+///
+/// Each [on](Self::on) arm is tried in turn, and the first whose type matches
+/// is called with a typed clone of the handle. The original is left untouched.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, Any};
+///
+/// fn describe(handle: &AnyHandle<dyn Any>) -> String {
+///     handle
+///         .visit()
+///         .on::<u32>(|number| format!("the number {}", *number.read()))
+///         .on::<String>(|text| format!("the text {:?}", *text.read()))
+///         .otherwise(|_| String::from("something else"))
+/// }
+///
+/// assert_eq!(describe(&AnyHandle::new(Box::new(7u32))), "the number 7");
+/// assert_eq!(describe(&AnyHandle::new(Box::new(String::from("hi")))), "the text \"hi\"");
+/// assert_eq!(describe(&AnyHandle::new(Box::new(1.5f32))), "something else");
+/// ```
+#[must_use = "a visit does nothing unless finished with `otherwise` or `finish`"]
+pub struct Visit<'a, R> {
+    handle: &'a AnyHandle<dyn Any>,
+    stored: TypeId,
+    result: Option<R>,
+}
+
+impl AnyHandle<dyn Any> {
+    /// Start dispatching on the type of the stored value. See [Visit].
+    pub fn visit<R>(&self) -> Visit<'_, R> {
+        Visit { handle: self, stored: self.stored_type_id(), result: None }
+    }
+}
+
+impl<'a, R> Visit<'a, R> {
+    /// Call `f` with a typed handle if the value is a `T` and no earlier arm matched.
+    pub fn on<T: 'static>(mut self, f: impl FnOnce(AnyHandle<T>) -> R) -> Self {
+        if self.result.is_none() && self.stored == TypeId::of::<T>() {
+            self.result = Some(f(self.handle.clone().cast_unchecked()));
+        }
+        self
+    }
+
+    /// Finish the visit, calling `f` with the erased handle if no arm matched.
+    pub fn otherwise(self, f: impl FnOnce(&'a AnyHandle<dyn Any>) -> R) -> R {
+        match self.result {
+            Some(result) => result,
+            None => f(self.handle),
+        }
+    }
+
+    /// Finish the visit, returning the result of the matching arm, if any.
+    pub fn finish(self) -> Option<R> {
+        self.result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_matching_arm_wins() {
+        let handle: AnyHandle<dyn Any> = AnyHandle::new(Box::new(3u8));
+        let calls = handle.visit().on::<u16>(|_| "u16").on::<u8>(|_| "u8").on::<u8>(|_| "again").finish();
+        assert_eq!(calls, Some("u8"));
+        assert_eq!(handle.visit::<()>().on::<u16>(|_| ()).finish(), None);
+
+        handle.visit().on::<u8>(|byte| *byte.write() += 1).finish();
+        assert_eq!(*handle.downcast::<u8>().ok().unwrap().read(), 4);
+    }
+}
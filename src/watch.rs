@@ -0,0 +1,264 @@
+use crate::{AccessError, AnyHandle, AnyHandleReadGuard, Slot};
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{PoisonError, RwLockReadGuard, RwLockWriteGuard};
+use std::time::{Duration, Instant};
+
+/// An exclusive lock on a handle's slot, which reports the write to
+/// subscribers when released, if it produced a new version.
+pub(crate) struct SlotWriteGuard<'a> {
+    guard: ManuallyDrop<RwLockWriteGuard<'a, Slot>>,
+    acquired_version: u64,
+}
+
+impl<'a> SlotWriteGuard<'a> {
+    pub(crate) fn new(guard: RwLockWriteGuard<'a, Slot>) -> Self {
+        let acquired_version = guard.version;
+        Self { guard: ManuallyDrop::new(guard), acquired_version }
+    }
+
+    /// Report the write and turn this into a read lock, without releasing it.
+    pub(crate) fn downgrade(self) -> SlotReadGuard<'a> {
+        let mut this = ManuallyDrop::new(self);
+        this.finish();
+        // SAFETY: `this` is never used or dropped again.
+        SlotReadGuard::new(RwLockWriteGuard::downgrade(unsafe { ManuallyDrop::take(&mut this.guard) }))
+    }
+
+    /// Finish the write, before the lock is released or downgraded.
+    fn finish(&mut self) {
+        #[cfg(feature = "contention-stats")]
+        if let Some(start) = self.guard.write_acquired.take() {
+            self.guard.stats.release_write(start);
+        }
+        self.notify();
+    }
+
+    fn notify(&mut self) {
+        // A write interrupted by a panic is reported too, so that waiters wake
+        // up and find the lock poisoned.
+        if self.guard.version == self.acquired_version {
+            return;
+        }
+        let version = self.guard.version;
+        let watchers = self.guard.watchers.get_mut().unwrap_or_else(PoisonError::into_inner);
+        watchers.retain(|(_, watcher)| watcher.send(version).is_ok());
+    }
+}
+
+impl Deref for SlotWriteGuard<'_> {
+    type Target = Slot;
+
+    #[inline(always)]
+    fn deref(&self) -> &Slot {
+        &self.guard
+    }
+}
+
+impl DerefMut for SlotWriteGuard<'_> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Slot {
+        &mut self.guard
+    }
+}
+
+impl Drop for SlotWriteGuard<'_> {
+    fn drop(&mut self) {
+        self.finish();
+        // SAFETY: the guard is not used again once dropped.
+        unsafe { ManuallyDrop::drop(&mut self.guard) }
+    }
+}
+
+/// A shared lock on a handle's slot, which records how long it was held when
+/// the `contention-stats` feature is enabled.
+pub(crate) struct SlotReadGuard<'a> {
+    guard: RwLockReadGuard<'a, Slot>,
+    #[cfg(feature = "contention-stats")]
+    acquired: Instant,
+}
+
+impl<'a> SlotReadGuard<'a> {
+    pub(crate) fn new(guard: RwLockReadGuard<'a, Slot>) -> Self {
+        Self {
+            guard,
+            #[cfg(feature = "contention-stats")]
+            acquired: Instant::now(),
+        }
+    }
+}
+
+impl Deref for SlotReadGuard<'_> {
+    type Target = Slot;
+
+    #[inline(always)]
+    fn deref(&self) -> &Slot {
+        &self.guard
+    }
+}
+
+#[cfg(feature = "contention-stats")]
+impl Drop for SlotReadGuard<'_> {
+    fn drop(&mut self) {
+        self.guard.stats.release_read(self.acquired);
+    }
+}
+
+static NEXT_WATCHER: AtomicU64 = AtomicU64::new(0);
+
+/// Add a subscriber to `slot`, returning its id and receiver.
+fn watch(slot: &Slot) -> (u64, Receiver<u64>) {
+    let id = NEXT_WATCHER.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = mpsc::channel();
+    slot.watchers.lock().unwrap_or_else(PoisonError::into_inner).push((id, sender));
+    (id, receiver)
+}
+
+/// Remove the subscriber `id` from `slot`.
+fn unwatch(slot: &Slot, id: u64) {
+    slot.watchers.lock().unwrap_or_else(PoisonError::into_inner).retain(|(watcher, _)| *watcher != id);
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Subscribe to writes to this object, so observers can react to changes
+    /// without polling. The receiver gets the new [version](AnyHandle::version)
+    /// of the object each time a write guard to it is released, from any clone.
+    ///
+    /// Dropping the receiver unsubscribes. Writes through
+    /// [get_mut](AnyHandle::get_mut), which bypass the lock, are not reported.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let title = AnyHandle::from_value(String::from("untitled"));
+    /// let changes = title.subscribe();
+    ///
+    /// let writer = title.clone();
+    /// std::thread::spawn(move || writer.write().push_str(" (1)")).join().unwrap();
+    ///
+    /// assert_eq!(changes.recv(), Ok(1));
+    /// assert_eq!(*title.read(), "untitled (1)");
+    /// ```
+    pub fn subscribe(&self) -> Receiver<u64> {
+        watch(&self.0.read().unwrap_or_else(PoisonError::into_inner)).1
+    }
+}
+
+impl<T: 'static> AnyHandle<T> {
+    /// Block until the object satisfies `pred`, returning a read guard to it
+    /// in that state. `pred` is checked now, then again after each write.
+    ///
+    /// ```
+    /// use any_handle::AnyHandle;
+    ///
+    /// let progress = AnyHandle::from_value(0u32);
+    /// let worker = progress.clone();
+    /// std::thread::spawn(move || {
+    ///     for _ in 0..10 {
+    ///         *worker.write() += 10;
+    ///     }
+    /// });
+    ///
+    /// assert_eq!(*progress.wait_until(|done| *done == 100), 100);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [read](AnyHandle::read).
+    pub fn wait_until(&self, pred: impl Fn(&T) -> bool) -> AnyHandleReadGuard<'_, T> {
+        self.wait_for(None, pred).unwrap()
+    }
+
+    /// Block until the object satisfies `pred`, like
+    /// [wait_until](AnyHandle::wait_until), for at most `timeout`. Fails with
+    /// [AccessError::TimedOut] if it still does not once the time is up.
+    pub fn wait_until_timeout(&self, timeout: Duration, pred: impl Fn(&T) -> bool) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        self.wait_for(Some(Instant::now() + timeout), pred)
+    }
+
+    fn wait_for(&self, deadline: Option<Instant>, pred: impl Fn(&T) -> bool) -> Result<AnyHandleReadGuard<'_, T>, AccessError> {
+        // Subscribe before the first check, so no write can slip in between.
+        let (id, changes) = watch(&self.0.read().unwrap_or_else(PoisonError::into_inner));
+        let result = loop {
+            let guard = match self.read_checked() {
+                Ok(guard) => guard,
+                Err(error) => break Err(error),
+            };
+            if pred(&guard) {
+                // Unsubscribing needs only the watchers' own lock, so the
+                // guard can be kept and returned in the state `pred` saw.
+                unwatch(&guard.0, id);
+                return Ok(guard);
+            }
+            drop(guard);
+            // The slot holds the sender until this call unsubscribes.
+            match deadline {
+                None => drop(changes.recv()),
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if changes.recv_timeout(remaining).is_err() {
+                        break Err(AccessError::TimedOut);
+                    }
+                }
+            }
+        };
+        unwatch(&self.0.read().unwrap_or_else(PoisonError::into_inner), id);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AnyHandle;
+
+    #[test]
+    fn writes_are_reported_once_released() {
+        let handle = AnyHandle::from_value(0u32);
+        let changes = handle.subscribe();
+        let mut guard = handle.write();
+        *guard += 1;
+        assert!(changes.try_recv().is_err());
+
+        let guard = guard.downgrade();
+        assert_eq!(changes.try_recv(), Ok(1));
+        drop(guard);
+        assert!(changes.try_recv().is_err());
+
+        handle.replace(5);
+        assert_eq!(changes.try_recv(), Ok(2));
+    }
+
+    #[test]
+    fn dropped_receivers_unsubscribe() {
+        let handle = AnyHandle::from_value(0u32);
+        drop(handle.subscribe());
+        *handle.write() += 1;
+        assert!(handle.0.read().unwrap().watchers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn wait_until_times_out() {
+        let handle = AnyHandle::from_value(1u32);
+        let timeout = std::time::Duration::from_millis(10);
+        assert_eq!(handle.wait_until_timeout(timeout, |value| *value == 2).err(), Some(crate::AccessError::TimedOut));
+        assert_eq!(*handle.wait_until_timeout(timeout, |value| *value == 1).unwrap(), 1);
+        assert!(handle.0.read().unwrap().watchers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn waiters_wake_when_a_writer_panics() {
+        let handle = AnyHandle::from_value(0u32);
+        std::thread::scope(|scope| {
+            let waiter = scope.spawn(|| handle.wait_until_timeout(std::time::Duration::from_secs(60), |value| *value == 1).err());
+            while handle.0.read().unwrap().watchers.lock().unwrap().is_empty() {
+                std::thread::yield_now();
+            }
+            let _ = std::panic::catch_unwind(|| {
+                let _guard = handle.write();
+                panic!("interrupted write");
+            });
+            assert_eq!(waiter.join().unwrap(), Some(crate::AccessError::Poisoned));
+        });
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{AnyHandle, Shared};
+use std::marker::PhantomData;
+use std::sync::{Arc, Weak};
+
+/// A non-owning reference to the object of an [AnyHandle], which does not
+/// keep it alive. Useful for back-references, such as from a child to its
+/// parent, that would otherwise form a cycle.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, Any};
+///
+/// let parent: AnyHandle<dyn Any> = AnyHandle::new(Box::new("root"));
+/// let back_reference = parent.downgrade();
+/// assert_eq!(parent.weak_count(), 1);
+///
+/// assert!(back_reference.upgrade().is_some());
+/// drop(parent);
+/// assert!(back_reference.upgrade().is_none());
+/// ```
+pub struct WeakAnyHandle<T: ?Sized>(Weak<Shared>, PhantomData<fn(T) -> T>);
+
+// SAFETY: as with AnyHandle, the shared slot only holds `Send + Sync` values.
+unsafe impl<T: ?Sized> Send for WeakAnyHandle<T> {}
+unsafe impl<T: ?Sized> Sync for WeakAnyHandle<T> {}
+
+impl<T: ?Sized> WeakAnyHandle<T> {
+    /// Create a weak handle that refers to nothing, and never upgrades.
+    pub fn new() -> Self {
+        Self(Weak::new(), PhantomData)
+    }
+
+    /// Get a strong handle to the object, or [None] if it has been dropped.
+    pub fn upgrade(&self) -> Option<AnyHandle<T>> {
+        self.0.upgrade().map(|shared| AnyHandle(shared, PhantomData))
+    }
+
+    /// Get the number of strong handles to the object.
+    #[inline(always)]
+    pub fn strong_count(&self) -> usize {
+        self.0.strong_count()
+    }
+
+    /// Get the number of weak handles to the object, or zero if it has been dropped.
+    #[inline(always)]
+    pub fn weak_count(&self) -> usize {
+        self.0.weak_count()
+    }
+}
+
+impl<T: ?Sized> AnyHandle<T> {
+    /// Make a [WeakAnyHandle] to this handle's object.
+    pub fn downgrade(&self) -> WeakAnyHandle<T> {
+        WeakAnyHandle(Arc::downgrade(&self.0), PhantomData)
+    }
+
+    /// Get the number of weak handles to this object.
+    #[inline(always)]
+    pub fn weak_count(&self) -> usize {
+        Arc::weak_count(&self.0)
+    }
+}
+
+impl<T: ?Sized> Clone for WeakAnyHandle<T> {
+    /// Make a new weak reference to the same object.
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: ?Sized> Default for WeakAnyHandle<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Any;
+
+    struct Node { parent: WeakAnyHandle<Node>, depth: u32 }
+
+    #[test]
+    fn back_references() {
+        let root: AnyHandle<Node> = AnyHandle::new(Box::new(Node { parent: WeakAnyHandle::new(), depth: 0 })).downcast().ok().unwrap();
+        let child = Node { parent: root.downgrade(), depth: 1 };
+
+        assert!(root.read().parent.upgrade().is_none());
+        assert_eq!(child.parent.upgrade().unwrap().read().depth, 0);
+        assert_eq!(child.parent.strong_count(), 1);
+        assert_eq!(root.weak_count(), 1);
+
+        drop(root);
+        assert!(child.parent.upgrade().is_none());
+        assert_eq!(child.depth, 1);
+        assert_eq!(WeakAnyHandle::<dyn Any>::default().weak_count(), 0);
+    }
+}
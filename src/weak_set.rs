@@ -0,0 +1,134 @@
+use crate::{Any, AnyHandle, Shared};
+use std::marker::PhantomData;
+use std::sync::{Arc, Weak};
+
+/// A set of handles that does not keep its members alive, for observer and
+/// listener lists.
+///
+/// Membership is by identity: two handles are the same member if they are
+/// clones of one another. Members are visited in insertion order, and entries
+/// whose objects have been dropped are skipped and pruned as the set is used.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, WeakHandleSet};
+///
+/// struct Listener { events: u32 }
+///
+/// let listener: AnyHandle<Listener> = AnyHandle::new(Box::new(Listener { events: 0 })).downcast().ok().unwrap();
+/// let mut listeners = WeakHandleSet::new();
+/// listeners.insert(&listener);
+///
+/// listeners.for_each_upgraded(|listener| listener.clone().write().events += 1);
+/// assert_eq!(listener.read().events, 1);
+///
+/// drop(listener);
+/// listeners.for_each_upgraded(|_| unreachable!());
+/// assert!(listeners.is_empty());
+/// ```
+pub struct WeakHandleSet<T: ?Sized = dyn Any> {
+    members: Vec<Weak<Shared>>,
+    marker: PhantomData<fn(T) -> T>,
+}
+
+impl<T: ?Sized> WeakHandleSet<T> {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self { members: Vec::new(), marker: PhantomData }
+    }
+
+    fn position(&self, handle: &AnyHandle<T>) -> Option<usize> {
+        let target = Arc::as_ptr(&handle.0);
+        self.members.iter().position(|member| std::ptr::eq(member.as_ptr(), target))
+    }
+
+    /// Add `handle` to the set. Returns `false` if it was already a member.
+    pub fn insert(&mut self, handle: &AnyHandle<T>) -> bool {
+        if self.position(handle).is_some() {
+            return false;
+        }
+        self.members.push(Arc::downgrade(&handle.0));
+        true
+    }
+
+    /// Remove `handle` from the set. Returns `false` if it was not a member.
+    pub fn remove(&mut self, handle: &AnyHandle<T>) -> bool {
+        let Some(position) = self.position(handle) else {
+            return false;
+        };
+        self.members.remove(position);
+        true
+    }
+
+    /// Check whether `handle` is a member of the set.
+    pub fn contains(&self, handle: &AnyHandle<T>) -> bool {
+        self.position(handle).is_some()
+    }
+
+    /// Remove every member whose object has been dropped.
+    pub fn prune(&mut self) {
+        self.members.retain(|member| member.strong_count() > 0);
+    }
+
+    /// Count the members whose objects are still alive.
+    pub fn len(&self) -> usize {
+        self.members.iter().filter(|member| member.strong_count() > 0).count()
+    }
+
+    /// Check whether the set has no living members.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Call `visit` with a strong handle to each living member, in insertion
+    /// order, pruning dead members along the way.
+    pub fn for_each_upgraded(&mut self, mut visit: impl FnMut(&AnyHandle<T>)) {
+        self.members.retain(|member| match member.upgrade() {
+            Some(shared) => {
+                visit(&AnyHandle(shared, PhantomData));
+                true
+            }
+            None => false,
+        });
+    }
+
+    /// Collect strong handles to every living member, in insertion order.
+    pub fn upgrade_all(&self) -> Vec<AnyHandle<T>> {
+        self.members.iter().filter_map(|member| Some(AnyHandle(member.upgrade()?, PhantomData))).collect()
+    }
+
+    /// Remove every member.
+    pub fn clear(&mut self) {
+        self.members.clear();
+    }
+}
+
+impl<T: ?Sized> Default for WeakHandleSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_membership() {
+        let a = AnyHandle::new(Box::new(1u8));
+        let b = AnyHandle::new(Box::new(1u8));
+        let mut set = WeakHandleSet::new();
+
+        assert!(set.insert(&a));
+        assert!(!set.insert(&a.clone()));
+        assert!(set.insert(&b));
+        assert!(set.contains(&a) && set.contains(&b));
+        assert_eq!(set.len(), 2);
+
+        assert!(set.remove(&a));
+        assert!(!set.remove(&a));
+        drop(b);
+        assert_eq!(set.len(), 0);
+        assert!(set.upgrade_all().is_empty());
+    }
+}
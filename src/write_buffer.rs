@@ -0,0 +1,157 @@
+use crate::{AccessError, Any, AnyHandle};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+type Command = Box<dyn FnOnce(&mut dyn Any) + Send>;
+
+/// A queue of deferred writes to handles, applied all at once.
+///
+/// This solves the "can't mutate while iterating" problem: while read
+/// guards are held, writes are [push](Self::push)ed into the buffer, and
+/// once the guards are released [apply](Self::apply) runs them.
+///
+/// Each handle's write lock is acquired once per `apply`, in the order the
+/// handles were first targeted, and the closures for that handle run in the
+/// order they were submitted.
+///
+/// # Example
+/// ```
+/// use any_handle::{AnyHandle, WriteBuffer};
+///
+/// struct Health(u32);
+///
+/// let handle: AnyHandle<Health> = AnyHandle::new(Box::new(Health(10))).downcast().ok().unwrap();
+/// let mut buffer = WriteBuffer::new();
+///
+/// {
+///     let health = handle.read();
+///     let damage = health.0 / 2;
+///     buffer.push(&handle, move |health: &mut Health| health.0 -= damage);
+/// }
+///
+/// buffer.apply();
+/// assert_eq!(handle.read().0, 5);
+/// ```
+#[derive(Default)]
+pub struct WriteBuffer {
+    targets: Vec<(AnyHandle<dyn Any>, Vec<Command>)>,
+    index: HashMap<usize, usize>,
+    len: usize,
+}
+
+impl WriteBuffer {
+    /// Create an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `write` to be run against the value of `handle`.
+    pub fn push<T: 'static>(&mut self, handle: &AnyHandle<T>, write: impl FnOnce(&mut T) + Send + 'static) {
+        let command: Command = Box::new(move |value| {
+            write(value.downcast_mut::<T>().expect("typed handle holds a different type"))
+        });
+        let address = Arc::as_ptr(&handle.0) as usize;
+        let targets = &mut self.targets;
+        let position = *self.index.entry(address).or_insert_with(|| {
+            targets.push((handle.clone().cast_unchecked(), Vec::new()));
+            targets.len() - 1
+        });
+        self.targets[position].1.push(command);
+        self.len += 1;
+    }
+
+    /// Get the number of queued writes.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether no writes are queued.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Run and remove every queued write.
+    ///
+    /// Writes targeting handles which cannot be written to, because they are
+    /// [revoked](AnyHandle::revoke), [read-only](AnyHandle::is_read_only) or
+    /// [poisoned](AnyHandle::is_poisoned), are discarded without touching
+    /// the handle. Those handles are returned with the reason, in the order
+    /// they were first targeted.
+    pub fn apply(&mut self) -> Vec<(AnyHandle<dyn Any>, AccessError)> {
+        self.index.clear();
+        self.len = 0;
+        let mut discarded = Vec::new();
+        for (handle, commands) in self.targets.drain(..) {
+            let error = match handle.write_checked() {
+                Ok(mut guard) => {
+                    for command in commands {
+                        command(guard.as_any_mut());
+                    }
+                    continue;
+                }
+                Err(error) => error,
+            };
+            discarded.push((handle, error));
+        }
+        discarded
+    }
+
+    /// Remove every queued write without running it.
+    pub fn clear(&mut self) {
+        self.targets.clear();
+        self.index.clear();
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Log { entries: Vec<u32> }
+
+    #[test]
+    fn applies_in_submission_order_per_handle() {
+        let log: AnyHandle<Log> = AnyHandle::new(Box::new(Log { entries: Vec::new() })).downcast().ok().unwrap();
+        let other: AnyHandle<Log> = AnyHandle::new(Box::new(Log { entries: Vec::new() })).downcast().ok().unwrap();
+        let mut buffer = WriteBuffer::new();
+
+        for i in 0..3 {
+            buffer.push(&log, move |log: &mut Log| log.entries.push(i));
+            buffer.push(&other, move |log: &mut Log| log.entries.push(i * 10));
+        }
+        assert_eq!(buffer.len(), 6);
+        let version = log.version();
+
+        buffer.apply();
+        assert!(buffer.is_empty());
+        assert_eq!(log.read().entries, [0, 1, 2]);
+        assert_eq!(other.read().entries, [0, 10, 20]);
+        assert_eq!(log.version(), version + 1);
+    }
+
+    #[test]
+    fn unwritable_targets_are_reported() {
+        static CONSTANT: u32 = 1;
+        let constant: AnyHandle<u32> = AnyHandle::from_static(&CONSTANT).downcast().ok().unwrap();
+        let revoked = AnyHandle::from_value(2u32);
+        revoked.revoke();
+        let live = AnyHandle::from_value(3u32);
+
+        let mut buffer = WriteBuffer::new();
+        for handle in [&constant, &revoked, &live] {
+            buffer.push(handle, |value: &mut u32| *value += 10);
+        }
+        let versions = (constant.version(), revoked.version());
+
+        let discarded = buffer.apply();
+        let reasons: Vec<AccessError> = discarded.iter().map(|(_, error)| *error).collect();
+        assert_eq!(reasons, [AccessError::ReadOnly, AccessError::Revoked]);
+        assert!(discarded[0].0.ptr_eq(&constant));
+        assert_eq!((constant.version(), revoked.version()), versions);
+        assert!(!constant.is_poisoned());
+        assert_eq!((*constant.read(), *live.read()), (1, 13));
+    }
+}